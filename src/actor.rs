@@ -1,22 +1,141 @@
-use macroquad::prelude::Vec2;
+use std::fmt::{Debug, Formatter};
+use glam::Vec2;
 use uuid::Uuid;
 use crate::collider::Collider;
-use crate::math::{vec2i32, Vec2I32};
+use crate::math::{vec2i32, Axis, Vec2I32};
+use crate::engine::SolidStorage;
+use crate::layers::{CollisionLayers, SolidFilter};
 use crate::solid::Solid;
 
-#[derive(Copy, Clone)]
-pub enum CollisionCallback {
+/// What happens to an actor when a move is blocked by a solid or a blocking actor.
+pub enum CollisionCallback<'a> {
+    /// Nothing happens, the actor just stops.
     None,
-    Squish
+    /// Marks the actor as squished. Used when a solid pushes an actor into another solid.
+    Squish,
+    /// Runs game code with the actor and the solid it hit, e.g. to bounce, stop or destroy it.
+    Custom(&'a mut dyn FnMut(&mut Actor, &CollisionInfo))
 }
 
+impl Debug for CollisionCallback<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CollisionCallback::None => write!(f, "None"),
+            CollisionCallback::Squish => write!(f, "Squish"),
+            CollisionCallback::Custom(_) => write!(f, "Custom(..)")
+        }
+    }
+}
+
+/// The kind of entity that stopped a move.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum CollisionTarget {
+    Solid(Uuid),
+    /// An actor with `blocks_actors` set that could not be pushed far enough.
+    Actor(Uuid)
+}
+
+/// What stopped a move.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CollisionInfo {
+    pub target: CollisionTarget,
+    /// Points out of the face that was hit, e.g. (0, 1) when landing on top of it.
+    pub normal: Vec2I32,
+    /// The solid's tags. Empty for actors.
+    pub tags: Vec<String>
+}
+
+impl CollisionInfo {
+    pub(crate) fn new(solid: &Solid, normal: Vec2I32) -> Self {
+        Self {
+            target: CollisionTarget::Solid(solid.uuid),
+            normal,
+            tags: solid.tags.clone()
+        }
+    }
+
+    pub(crate) fn actor(actor_uuid: Uuid, normal: Vec2I32) -> Self {
+        Self {
+            target: CollisionTarget::Actor(actor_uuid),
+            normal,
+            tags: vec![]
+        }
+    }
+
+    pub fn solid_uuid(&self) -> Option<Uuid> {
+        match self.target {
+            CollisionTarget::Solid(uuid) => Some(uuid),
+            _ => None
+        }
+    }
+
+    pub fn actor_uuid(&self) -> Option<Uuid> {
+        match self.target {
+            CollisionTarget::Actor(uuid) => Some(uuid),
+            _ => None
+        }
+    }
+}
+
+/// What happened along one axis of a move.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct AxisMoveResult {
+    /// Whole pixels actually moved, signed like the requested distance.
+    pub travelled: i32,
+    /// Set when something blocked the move.
+    pub hit: Option<CollisionInfo>
+}
+
+impl AxisMoveResult {
+    pub fn blocked(&self) -> bool {
+        self.hit.is_some()
+    }
+}
+
+/// What happened during a move. X is resolved before Y.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct MoveResult {
+    pub x: AxisMoveResult,
+    pub y: AxisMoveResult
+}
+
+impl MoveResult {
+    pub fn blocked(&self) -> bool {
+        self.x.blocked() || self.y.blocked()
+    }
+}
+
+/// Decides which solids the engine makes an actor ride before solids move each frame.
+#[derive(Copy, Clone, Debug, Default)]
+pub enum RideMode {
+    /// The engine never makes the actor ride anything. Riding can still be set by hand with `PhysicsEngine::ride`.
+    #[default]
+    None,
+    /// Rides solids directly below it, using the actor's down direction.
+    Standing,
+    /// Rides solids below it or touching its sides.
+    StandingOrClinging,
+    /// Rides every solid within one pixel of it for which the predicate returns true.
+    Custom(fn(&Actor, &Solid) -> bool)
+}
+
+#[derive(Clone, Debug)]
 pub struct Actor {
     pub uuid: Uuid,
     pub remainder: Vec2,
     pub collider: Collider,
     pub squished: bool,
     pub last_push_amount: Vec2I32, // The distance you were last pushed
-    riding: Vec<Uuid>,
+    /// Makes other actors moving through the engine collide with this actor and push it.
+    pub blocks_actors: bool,
+    pub ride_mode: RideMode,
+    /// One pixel step towards the floor, (0, -1) by default. Used by the ride mode and when dropping through platforms.
+    /// Slopes only have a floor on top, so actors only stick to them walking down with the default.
+    pub down: Vec2I32,
+    pub layers: CollisionLayers,
+    // One way solids ignored until drop_through_frames runs out.
+    pub(crate) dropping_through: Vec<Uuid>,
+    pub(crate) drop_through_frames: u32
 }
 
 impl Actor {
@@ -28,92 +147,144 @@ impl Actor {
             collider,
             squished: false,
             last_push_amount: vec2i32(0, 0),
-            riding: vec![]
+            blocks_actors: false,
+            ride_mode: RideMode::None,
+            down: vec2i32(0, -1),
+            layers: CollisionLayers::DEFAULT,
+            dropping_through: vec![],
+            drop_through_frames: 0
         }
     }
 
-    pub(crate) fn move_actor(&mut self, distance: Vec2, callback: CollisionCallback, solids: &Vec<Solid>) {
-        self.move_x(distance.x, callback, solids);
-        self.move_y(distance.y, callback, solids);
+    /// The solids that can block this actor: those on layers it collides with that it is not dropping through.
+    pub fn solid_filter(&self) -> SolidFilter<'_> {
+        SolidFilter {
+            layers: self.layers,
+            ignored: &self.dropping_through
+        }
     }
 
-    pub(crate) fn move_x(&mut self, distance: f32, callback: CollisionCallback, solids: &Vec<Solid>) {
-        self.remainder.x += distance;
-        let move_amount = self.remainder.x as i32;
-        self.remainder.x -= move_amount as f32;
-
-        self.move_x_exact(move_amount, callback, solids);
+    /// Checks if the actor is currently dropping through the one way solid.
+    pub fn is_dropping_through(&self, solid_uuid: Uuid) -> bool {
+        self.dropping_through.contains(&solid_uuid)
     }
 
-    pub(crate) fn move_y(&mut self, distance: f32, callback: CollisionCallback, solids: &Vec<Solid>) {
-        self.remainder.y += distance;
-        let move_amount = self.remainder.y as i32;
-        self.remainder.y -= move_amount as f32;
+    /// Checks if the ride mode wants this actor to ride the solid.
+    pub fn wants_to_ride(&self, solid: &Solid) -> bool {
+        if !self.solid_filter().allows(solid) { return false };
 
-        self.move_y_exact(move_amount, callback, solids);
+        let touching = |offset: Vec2I32| solid.is_overlapping(offset, &self.collider) && solid.blocks(&self.collider, offset);
+        let standing = || touching(self.down);
+        let clinging = || {
+            let side = vec2i32(self.down.y, self.down.x);
+            touching(side) || touching(vec2i32(-side.x, -side.y))
+        };
+
+        match self.ride_mode {
+            RideMode::None => false,
+            RideMode::Standing => standing(),
+            RideMode::StandingOrClinging => standing() || clinging(),
+            RideMode::Custom(predicate) => predicate(self, solid)
+        }
     }
 
-    pub fn move_exact(&mut self, distance: Vec2I32, callback: CollisionCallback, solids: &Vec<Solid>) {
-        self.move_x_exact(distance.x, callback, solids);
-        self.move_y_exact(distance.y, callback, solids);
+    /// Adds the distance to the remainder and takes out the whole pixels that are ready to move.
+    pub(crate) fn take_whole_pixels(&mut self, distance: Vec2) -> Vec2I32 {
+        self.remainder += distance;
+        let move_amount = vec2i32(self.remainder.x as i32, self.remainder.y as i32);
+        self.remainder.x -= move_amount.x as f32;
+        self.remainder.y -= move_amount.y as f32;
+
+        move_amount
     }
 
-    pub fn move_x_exact(&mut self, distance: i32, callback: CollisionCallback, solids: &Vec<Solid>) {
-        let mut move_amount = distance;
-        let step = move_amount.signum();
-        while move_amount != 0 {
+    /// Moves against solids only, the same way [`crate::engine::PhysicsEngine::move_actor`] does but without pushing
+    /// or being blocked by other actors. Actors on a slope stick to it while walking down, unless they also move up.
+    /// Like slopes, sticking assumes y points up, so it only happens while `down` is (0, -1).
+    pub fn move_exact(&mut self, distance: Vec2I32, callback: &mut CollisionCallback, solids: &SolidStorage) -> MoveResult {
+        let slope_step = match self.down == vec2i32(0, -1) {
+            true => solids.slope_step_under(&self.collider, &self.solid_filter()),
+            false => None
+        };
 
-            for solid in solids {
-                // Check for collision
-                if self.collider.is_overlapping(vec2i32(step, 0), &solid.collider) {
-                    self.remainder.x = 0.0;
-                    self.handle_callback(callback);
-                    return;
-                }
+        let x = self.move_x_exact(distance.x, callback, solids);
+        if let (Some(step), true) = (slope_step, distance.y <= 0) {
+            if let Some(drop) = solids.ground_within(&self.collider, x.travelled.abs() * step, &self.solid_filter()) {
+                self.collider.y -= drop;
             }
+        }
 
-            self.collider.x += step;
-            move_amount -= step;
+        MoveResult {
+            x,
+            y: self.move_y_exact(distance.y, callback, solids)
         }
     }
 
-    pub fn move_y_exact(&mut self, distance: i32, callback: CollisionCallback, solids: &Vec<Solid>) {
-        let mut move_amount = distance;
-        let step = move_amount.signum();
-        while move_amount != 0 {
-            for solid in solids {
-                // Check for collision
-                if self.collider.is_overlapping(vec2i32(0, step), &solid.collider) {
-                    self.remainder.y = 0.0;
-                    self.handle_callback(callback);
-                    return;
+    /// Walks up slopes in the way instead of stopping at them.
+    pub fn move_x_exact(&mut self, distance: i32, callback: &mut CollisionCallback, solids: &SolidStorage) -> AxisMoveResult {
+        self.move_exact_along(Axis::X, distance, callback, solids)
+    }
+
+    pub fn move_y_exact(&mut self, distance: i32, callback: &mut CollisionCallback, solids: &SolidStorage) -> AxisMoveResult {
+        self.move_exact_along(Axis::Y, distance, callback, solids)
+    }
+
+    fn move_exact_along(&mut self, axis: Axis, distance: i32, callback: &mut CollisionCallback, solids: &SolidStorage) -> AxisMoveResult {
+        let mut travelled = 0;
+
+        loop {
+            let remaining = distance - travelled;
+            if remaining == 0 { return AxisMoveResult { travelled, hit: None } };
+            let sign = remaining.signum();
+
+            match solids.blocking_hit(&self.collider, axis, remaining, &self.solid_filter()) {
+                Some((free, _, Some(height))) => {
+                    self.collider.translate(axis, free * sign);
+                    self.collider.y += height;
+                    travelled += free * sign;
+                }
+                Some((free, info, None)) => {
+                    self.stop(axis, free * sign, callback, &info);
+                    return AxisMoveResult { travelled: travelled + free * sign, hit: Some(info) };
+                }
+                None => {
+                    self.collider.translate(axis, remaining);
+                    return AxisMoveResult { travelled: distance, hit: None };
                 }
             }
+        }
+    }
 
-            self.collider.y += step;
-            move_amount -= step;
+    /// Moves the last few pixels up to whatever blocked the move, then reacts to the collision.
+    pub(crate) fn stop(&mut self, axis: Axis, travelled: i32, callback: &mut CollisionCallback, info: &CollisionInfo) {
+        self.collider.translate(axis, travelled);
+        match axis {
+            Axis::X => self.remainder.x = 0.0,
+            Axis::Y => self.remainder.y = 0.0
         }
+        self.handle_callback(callback, info);
     }
 
-    pub(crate) fn handle_callback(&mut self, callback: CollisionCallback) {
+    pub(crate) fn handle_callback(&mut self, callback: &mut CollisionCallback, info: &CollisionInfo) {
         match callback {
             CollisionCallback::None => { }
             CollisionCallback::Squish => {
                 self.squished = true;
             }
+            CollisionCallback::Custom(callback) => {
+                callback(self, info);
+            }
         }
     }
 
     pub(crate) fn update(&mut self) {
-        self.riding.clear();
         self.last_push_amount = vec2i32(0, 0);
-    }
 
-    pub(crate) fn ride(&mut self, solid_uuid: Uuid) {
-        self.riding.push(solid_uuid);
-    }
-
-    pub(crate) fn is_riding(&self, solid_uuid: Uuid) -> bool {
-        self.riding.contains(&solid_uuid)
+        if self.drop_through_frames > 0 {
+            self.drop_through_frames -= 1;
+            if self.drop_through_frames == 0 {
+                self.dropping_through.clear();
+            }
+        }
     }
 }
\ No newline at end of file
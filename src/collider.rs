@@ -1,4 +1,8 @@
-use crate::math::Vec2I32;
+use glam::Vec2;
+use crate::math::{Axis, Vec2I32};
+
+/// Furthest any collider built from float coordinates reaches from the origin, so its edges always fit in an i32.
+pub const WORLD_LIMIT: i32 = 1 << 29;
 
 /// Collider with x, y, width, and height that can check for AABB Collision Overlap with any other collider.
 /// Can Be Disabled
@@ -23,6 +27,16 @@ impl Collider {
         }
     }
 
+    /// Creates the smallest collider covering the area between the two points, with the given margin of pixels
+    /// around it. The area is clamped to [`WORLD_LIMIT`], so huge or infinite points are fine.
+    pub fn covering(min: Vec2, max: Vec2, margin: i32) -> Self {
+        let limit = WORLD_LIMIT as f32;
+        let min = (min.floor() - margin as f32).clamp(Vec2::splat(-limit), Vec2::splat(limit));
+        let max = (max.ceil() + margin as f32).clamp(Vec2::splat(-limit), Vec2::splat(limit));
+
+        Self::new(min.x as i32, min.y as i32, (max.x - min.x) as i32, (max.y - min.y) as i32)
+    }
+
     pub fn min_x(&self) -> i32 {
         self.x
     }
@@ -43,6 +57,70 @@ impl Collider {
         self.y + self.height / 2
     }
 
+    /// Returns a copy moved by the offset.
+    pub fn moved(&self, offset: Vec2I32) -> Collider {
+        Collider {
+            x: self.x + offset.x,
+            y: self.y + offset.y,
+            ..self.clone()
+        }
+    }
+
+    /// Returns the area this collider covers while moving by the offset.
+    pub fn swept(&self, offset: Vec2I32) -> Collider {
+        Collider {
+            x: self.x.min(self.x + offset.x),
+            y: self.y.min(self.y + offset.y),
+            width: self.width + offset.x.abs(),
+            height: self.height + offset.y.abs(),
+            enabled: self.enabled
+        }
+    }
+
+    /// Sweeps along either axis, see [`Collider::sweep_x`] and [`Collider::sweep_y`].
+    pub fn sweep(&self, axis: Axis, distance: i32, other: &Collider) -> Option<i32> {
+        match axis {
+            Axis::X => self.sweep_x(distance, other),
+            Axis::Y => self.sweep_y(distance, other)
+        }
+    }
+
+    /// Moves the collider along the axis.
+    pub fn translate(&mut self, axis: Axis, amount: i32) {
+        match axis {
+            Axis::X => self.x += amount,
+            Axis::Y => self.y += amount
+        }
+    }
+
+    /// Returns how many pixels this collider can move along x before it would overlap the other collider,
+    /// or None if the full distance is free. Gives the same answer as stepping one pixel at a time.
+    pub fn sweep_x(&self, distance: i32, other: &Collider) -> Option<i32> {
+        if !(self.enabled && other.enabled) { return None };
+        // Only colliders sharing some of the y axis can block.
+        if !(self.max_y() > other.min_y() && self.min_y() < other.max_y()) { return None };
+
+        if distance > 0 {
+            first_blocked_step(other.min_x() - self.max_x(), other.max_x() - self.min_x(), distance)
+        } else {
+            first_blocked_step(self.min_x() - other.max_x(), self.max_x() - other.min_x(), -distance)
+        }
+    }
+
+    /// Returns how many pixels this collider can move along y before it would overlap the other collider,
+    /// or None if the full distance is free. Gives the same answer as stepping one pixel at a time.
+    pub fn sweep_y(&self, distance: i32, other: &Collider) -> Option<i32> {
+        if !(self.enabled && other.enabled) { return None };
+        // Only colliders sharing some of the x axis can block.
+        if !(self.max_x() > other.min_x() && self.min_x() < other.max_x()) { return None };
+
+        if distance > 0 {
+            first_blocked_step(other.min_y() - self.max_y(), other.max_y() - self.min_y(), distance)
+        } else {
+            first_blocked_step(self.min_y() - other.max_y(), self.max_y() - other.min_y(), -distance)
+        }
+    }
+
     /// Checks for AABB Collision between collider and self with an offset
     pub fn is_overlapping(&self, offset: Vec2I32, other: &Collider) -> bool {
         if !(self.enabled && other.enabled) { return false };
@@ -51,4 +129,15 @@ impl Collider {
             && (self.max_y() + offset.y) > other.min_y()
             && (self.min_y() + offset.y) < other.max_y()
     }
-}
\ No newline at end of file
+}
+
+/// Steps `k` taken before moving one more pixel overlaps, where that next pixel overlaps whenever `gap <= k <= depth - 2`.
+/// `gap` is the distance to the near edge and `depth` the distance to the far edge, both along the direction of motion.
+fn first_blocked_step(gap: i32, depth: i32, distance: i32) -> Option<i32> {
+    let free = gap.max(0);
+    if free < distance && free <= depth - 2 {
+        Some(free)
+    } else {
+        None
+    }
+}
@@ -1,60 +1,253 @@
-use macroquad::math::Vec2;
+use std::collections::{BTreeSet, HashMap};
+use glam::Vec2;
 use uuid::Uuid;
-use crate::actor::CollisionCallback;
-use crate::math::{Vec2I32};
+use crate::actor::{CollisionCallback, CollisionInfo, MoveResult, RideMode};
+use crate::arena::Handle;
+use crate::error::PhysicsError;
+use crate::events::PhysicsEvent;
+use crate::layers::{CollisionLayers, SolidFilter};
+use crate::math::{vec2i32, Axis, Vec2I32};
 use crate::prelude::{Actor, Collider, Solid};
+use crate::slope::Slope;
 use crate::solid::SolidInteraction;
+use crate::spatial_hash::Broadphase;
+use crate::storage::{Storage, Stored};
+use crate::tile_grid::{Tile, TileGrid};
+use crate::timestep::FixedTimestep;
+#[cfg(feature = "expanded_engine")]
+use crate::expanded_engine::velocity::VelocityStorage;
+use crate::trigger::Trigger;
 
 pub struct PhysicsEngine {
     pub actor_storage: ActorStorage,
-    pub solid_storage: SolidStorage
+    pub solid_storage: SolidStorage,
+    pub trigger_storage: TriggerStorage,
+    /// Tick length and leftover time for [`PhysicsEngine::step`].
+    pub timestep: FixedTimestep,
+    /// Velocities moved by [`PhysicsEngine::integrate`].
+    #[cfg(feature = "expanded_engine")]
+    pub velocities: VelocityStorage,
+    pub(crate) events: Vec<PhysicsEvent>,
+    // (actor, solid) pairs that were riding when end_update last ran.
+    riding_last_frame: BTreeSet<(Uuid, Uuid)>,
+    // (actor, trigger) pairs that were overlapping when end_update last ran.
+    inside_last_frame: BTreeSet<(Uuid, Uuid)>,
+    riding_detected: bool
 }
 
-pub struct ActorStorage {
-    pub actors: Vec<Actor>
+pub type ActorHandle = Handle<Actor>;
+pub type SolidHandle = Handle<Solid>;
+pub type TriggerHandle = Handle<Trigger>;
+
+/// Stores every actor, see [`Storage`].
+pub type ActorStorage = Storage<Actor>;
+/// Stores every solid, see [`Storage`].
+pub type SolidStorage = Storage<Solid>;
+/// Stores every trigger, see [`Storage`].
+pub type TriggerStorage = Storage<Trigger>;
+
+/// The actors riding each solid, keyed by the solid's uuid. Kept next to the actors and cleared every end_update.
+#[derive(Debug, Default)]
+pub struct Riders {
+    riders: HashMap<Uuid, Vec<ActorHandle>>
+}
+
+impl Stored for Actor {
+    type Extra = Riders;
+
+    fn uuid(&self) -> Uuid {
+        self.uuid
+    }
+
+    fn collider(&self) -> &Collider {
+        &self.collider
+    }
+
+    fn duplicate_error(uuid: Uuid) -> PhysicsError {
+        PhysicsError::DuplicateActor(uuid)
+    }
+
+    fn not_found_error(uuid: Uuid) -> PhysicsError {
+        PhysicsError::ActorNotFound(uuid)
+    }
+
+    fn despawned_error(uuid: Uuid) -> PhysicsError {
+        PhysicsError::ActorDespawned(uuid)
+    }
+
+    fn removed(extra: &mut Riders, handle: ActorHandle) {
+        for riders in extra.riders.values_mut() {
+            riders.retain(|rider| *rider != handle);
+        }
+    }
+}
+
+impl Stored for Solid {
+    type Extra = ();
+
+    fn uuid(&self) -> Uuid {
+        self.uuid
+    }
+
+    fn collider(&self) -> &Collider {
+        &self.collider
+    }
+
+    fn duplicate_error(uuid: Uuid) -> PhysicsError {
+        PhysicsError::DuplicateSolid(uuid)
+    }
+
+    fn not_found_error(uuid: Uuid) -> PhysicsError {
+        PhysicsError::SolidNotFound(uuid)
+    }
+
+    fn despawned_error(uuid: Uuid) -> PhysicsError {
+        PhysicsError::SolidDespawned(uuid)
+    }
+}
+
+impl Stored for Trigger {
+    type Extra = ();
+
+    fn uuid(&self) -> Uuid {
+        self.uuid
+    }
+
+    fn collider(&self) -> &Collider {
+        &self.collider
+    }
+
+    fn duplicate_error(uuid: Uuid) -> PhysicsError {
+        PhysicsError::DuplicateTrigger(uuid)
+    }
+
+    fn not_found_error(uuid: Uuid) -> PhysicsError {
+        PhysicsError::TriggerNotFound(uuid)
+    }
+
+    fn despawned_error(uuid: Uuid) -> PhysicsError {
+        PhysicsError::TriggerDespawned(uuid)
+    }
 }
 
 impl ActorStorage {
-    pub fn get_actor(&mut self, actor_uuid: Uuid) -> Result<&mut Actor, String> {
-        let mut my_actor_option = None;
-        for actor in self.actors.iter_mut() {
-            if actor.uuid == actor_uuid {
-                my_actor_option = Some(actor)
-            }
+    pub fn get_actor(&mut self, actor_uuid: Uuid) -> Result<&mut Actor, PhysicsError> {
+        self.get_by_uuid(actor_uuid)
+    }
+
+    /// Makes the actor ride the solid until the next end_update.
+    pub fn ride(&mut self, handle: ActorHandle, solid_uuid: Uuid) {
+        let riders = self.extra.riders.entry(solid_uuid).or_default();
+        if !riders.contains(&handle) {
+            riders.push(handle);
         }
+    }
+
+    pub fn is_riding(&self, handle: ActorHandle, solid_uuid: Uuid) -> bool {
+        self.riders_of(solid_uuid).contains(&handle)
+    }
 
-        return if let Some(my_actor) = my_actor_option {
-            Ok(my_actor)
-        } else {
-            Err(format!("Expected an actor with uuid {}, but did not find one.", actor_uuid))
+    /// Returns every actor riding the solid.
+    pub fn riders_of(&self, solid_uuid: Uuid) -> &[ActorHandle] {
+        match self.extra.riders.get(&solid_uuid) {
+            Some(riders) => riders,
+            None => &[]
         }
     }
-}
 
-pub struct SolidStorage {
-    pub solids: Vec<Solid>
+    /// Every (actor uuid, solid uuid) pair currently riding, in a stable order.
+    pub fn riding_pairs(&self) -> BTreeSet<(Uuid, Uuid)> {
+        let mut pairs = BTreeSet::new();
+        for (solid_uuid, riders) in self.extra.riders.iter() {
+            for rider in riders {
+                if let Some(actor) = self.get(*rider) {
+                    pairs.insert((actor.uuid, *solid_uuid));
+                }
+            }
+        }
+        pairs
+    }
+
+    pub(crate) fn stop_riding_solid(&mut self, solid_uuid: Uuid) {
+        self.extra.riders.remove(&solid_uuid);
+    }
+
+    pub(crate) fn clear_riding(&mut self) {
+        self.extra.riders.clear();
+    }
 }
 
 impl SolidStorage {
-    pub fn get_solid(&mut self, solid_uuid: Uuid) -> Result<&mut Solid, String> {
-        let mut my_solid_option = None;
-        for solid in self.solids.iter_mut() {
-            if solid.uuid == solid_uuid {
-                my_solid_option = Some(solid)
+    pub fn get_solid(&mut self, solid_uuid: Uuid) -> Result<&mut Solid, PhysicsError> {
+        self.get_by_uuid(solid_uuid)
+    }
+
+    /// Finds the closest solid blocking the collider as it moves along the axis, and how far it can move before touching it.
+    /// Ties go to the solid stored first. Only solids the filter allows can block.
+    pub fn first_hit(&self, collider: &Collider, axis: Axis, distance: i32, filter: &SolidFilter) -> Option<(i32, &Solid)> {
+        let mut closest: Option<(i32, &Solid)> = None;
+
+        for handle in self.query(&collider.swept(axis.vec(distance))) {
+            let solid = self.get(handle).expect("broadphase returned a removed solid");
+            if !filter.allows(solid) { continue };
+
+            if let Some(free) = solid.sweep(collider, axis, distance) {
+                if closest.map_or(true, |(closest_free, _)| free < closest_free) {
+                    closest = Some((free, solid));
+                }
             }
         }
 
-        return if let Some(my_solid) = my_solid_option {
-            Ok(my_solid)
-        } else {
-            Err(format!("Expected an solid with id {}, but did not find one.", solid_uuid))
+        closest
+    }
+
+    /// Checks if a collider that hit the slope after moving `free` pixels along x can step up onto it and keep walking,
+    /// and returns how far it has to rise.
+    pub fn climb(&self, collider: &Collider, slope: &Solid, free: i32, sign: i32, filter: &SolidFilter) -> Option<i32> {
+        let mut at = collider.clone();
+        at.translate(Axis::X, free * sign);
+        let height = slope.climb_height(&at, sign)?;
+        if self.first_hit(&at, Axis::Y, height, filter).is_some() { return None };
+
+        at.translate(Axis::Y, height);
+        self.first_hit(&at, Axis::X, sign, filter).is_none().then_some(height)
+    }
+
+    /// What stops the collider moving along the axis: how far it gets, what it hit, and how high it can step up
+    /// to keep going when that is a slope it can climb. None if the full distance is free.
+    pub(crate) fn blocking_hit(&self, collider: &Collider, axis: Axis, distance: i32, filter: &SolidFilter) -> Option<(i32, CollisionInfo, Option<i32>)> {
+        let (free, solid) = self.first_hit(collider, axis, distance, filter)?;
+        let sign = distance.signum();
+        let climb = match axis {
+            Axis::X => self.climb(collider, solid, free, sign, filter),
+            Axis::Y => None
+        };
+
+        Some((free, CollisionInfo::new(solid, axis.vec(-sign)), climb))
+    }
+
+    /// The step height of the steepest slope the collider stands on, if it stands on one.
+    pub fn slope_step_under(&self, collider: &Collider, filter: &SolidFilter) -> Option<i32> {
+        self.query(&collider.swept(vec2i32(0, -1))).into_iter()
+            .filter_map(|handle| self.get(handle))
+            .filter(|solid| filter.allows(solid))
+            .filter_map(|solid| solid.slope_step_under(collider))
+            .max()
+    }
+
+    /// How far the collider has to drop to land on something at most `max` pixels below it.
+    /// None if it is already standing or nothing is close enough.
+    pub fn ground_within(&self, collider: &Collider, max: i32, filter: &SolidFilter) -> Option<i32> {
+        match self.first_hit(collider, Axis::Y, -(max + 1), filter) {
+            Some((free, _)) if free > 0 => Some(free),
+            _ => None
         }
     }
 
-    pub fn get_solids_with_tag(&mut self, tag: &str) -> Vec<Uuid> {
+    pub fn get_solids_with_tag(&self, tag: &str) -> Vec<Uuid> {
         let mut solids = vec![];
 
-        for solid in self.solids.iter_mut() {
+        for solid in self.iter() {
             if solid.has_tag(tag) {
                 solids.push(solid.uuid);
             }
@@ -64,76 +257,196 @@ impl SolidStorage {
     }
 }
 
+impl TriggerStorage {
+    pub fn get_trigger(&mut self, trigger_uuid: Uuid) -> Result<&mut Trigger, PhysicsError> {
+        self.get_by_uuid(trigger_uuid)
+    }
+
+    pub fn get_triggers_with_tag(&self, tag: &str) -> Vec<Uuid> {
+        let mut triggers = vec![];
+
+        for trigger in self.iter() {
+            if trigger.has_tag(tag) {
+                triggers.push(trigger.uuid);
+            }
+        }
+
+        triggers
+    }
+}
+
+impl Default for PhysicsEngine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl PhysicsEngine {
     pub fn new() -> Self {
+        Self::with_broadphase(Broadphase::default())
+    }
+
+    pub fn with_broadphase(broadphase: Broadphase) -> Self {
         Self {
-            actor_storage: ActorStorage {actors: vec![]},
-            solid_storage: SolidStorage {solids: vec![]}
+            actor_storage: ActorStorage::new(broadphase),
+            solid_storage: SolidStorage::new(broadphase),
+            trigger_storage: TriggerStorage::new(broadphase),
+            timestep: FixedTimestep::default(),
+            #[cfg(feature = "expanded_engine")]
+            velocities: VelocityStorage::default(),
+            events: vec![],
+            riding_last_frame: BTreeSet::new(),
+            inside_last_frame: BTreeSet::new(),
+            riding_detected: false
         }
     }
 
-    fn handle_interactions(&mut self, interactions: &Vec<SolidInteraction>, ignore_uuid: Uuid) {
+    /// Changes how collision candidates are found, e.g. the spatial hash cell size.
+    /// Switching to [`Broadphase::BruteForce`] gives identical results and can be used to verify the spatial hash.
+    pub fn set_broadphase(&mut self, broadphase: Broadphase) {
+        self.actor_storage.set_broadphase(broadphase);
+        self.solid_storage.set_broadphase(broadphase);
+        self.trigger_storage.set_broadphase(broadphase);
+    }
+
+    /// Brings the broadphase up to date after colliders were edited directly rather than moved through the engine.
+    /// Queries stay correct without it, since edited entities are checked one by one until synced, but syncing makes them fast again.
+    /// This also happens automatically in [`PhysicsEngine::end_update`].
+    pub fn sync_broadphase(&mut self) {
+        self.actor_storage.sync_all();
+        self.solid_storage.sync_all();
+        self.trigger_storage.sync_all();
+    }
 
-        // Retain the solid that was the interaction has.
-        let mut solids = self.solid_storage.solids.clone();
-        solids.retain(|x| x.uuid != ignore_uuid);
+    fn handle_interactions(&mut self, interactions: &Vec<SolidInteraction>, pushing_solid: SolidHandle, callback: &mut CollisionCallback) {
+        // Disable the pushing solid while the actors move so that they are not blocked by it.
+        let pushing_enabled = match self.solid_storage.get_mut(pushing_solid) {
+            Some(solid) => std::mem::replace(&mut solid.collider.enabled, false),
+            None => return
+        };
+
+        let mut pusher = self.solid_storage.get(pushing_solid).expect("pushing solid was just looked up").clone();
+        pusher.collider.enabled = true;
+        let pushing_uuid = pusher.uuid;
+        let squisher = match callback {
+            CollisionCallback::Squish => Some(pushing_uuid),
+            _ => None
+        };
 
         for interaction in interactions {
-            for actor in self.actor_storage.actors.iter_mut() {
-                if actor.uuid == interaction.actor_uuid {
-                    actor.last_push_amount.x += interaction.motion.x;
-                    actor.last_push_amount.y += interaction.motion.y;
-                    actor.move_exact(interaction.motion, CollisionCallback::Squish, &solids)
+            let actor = match self.actor_storage.get_mut(interaction.actor) {
+                Some(actor) => actor,
+                None => continue
+            };
+
+            let motion = match interaction.carried {
+                true => interaction.motion,
+                // Pushing an earlier actor may have pushed this one already, so work out what is left from where it is now.
+                false => match pusher.is_overlapping(vec2i32(0, 0), &actor.collider) {
+                    true => pusher.push_out_motion(&actor.collider, interaction.motion),
+                    false => continue
                 }
+            };
+
+            actor.last_push_amount.x += motion.x;
+            actor.last_push_amount.y += motion.y;
+            let result = self.move_actor_exact(interaction.actor, motion, callback, squisher);
+
+            self.events.push(PhysicsEvent::ActorPushed { actor_uuid: interaction.actor_uuid, solid_uuid: pushing_uuid, motion });
+            for collision in [result.x.hit, result.y.hit].into_iter().flatten() {
+                self.record_blocked(interaction.actor_uuid, collision, squisher);
             }
         }
+
+        if let Some(solid) = self.solid_storage.get_mut(pushing_solid) {
+            solid.collider.enabled = pushing_enabled;
+        }
     }
 
-    pub fn move_solid(&mut self, solid_uuid: Uuid, distance: Vec2) -> Result<(), String> {
-        for solid_index in 0..self.solid_storage.solids.len() {
-            if self.solid_storage.solids[solid_index].uuid == solid_uuid {
-                let y_interactions = self.solid_storage.solids[solid_index].move_y(distance.y, &mut self.actor_storage.actors);
-                self.handle_interactions(&y_interactions, self.solid_storage.solids[solid_index].uuid);
+    /// Returns the handle of the actor with the given UUID.
+    pub fn actor_handle(&self, actor_uuid: Uuid) -> Option<ActorHandle> {
+        self.actor_storage.handle_of(actor_uuid)
+    }
 
-                let x_interactions = self.solid_storage.solids[solid_index].move_x(distance.x, &mut self.actor_storage.actors);
-                self.handle_interactions(&x_interactions, self.solid_storage.solids[solid_index].uuid);
+    /// Returns the handle of the solid with the given UUID.
+    pub fn solid_handle(&self, solid_uuid: Uuid) -> Option<SolidHandle> {
+        self.solid_storage.handle_of(solid_uuid)
+    }
 
-                return Ok(())
-            }
-        }
+    /// Moves a solid, pushing and carrying actors. Actors pushed into another solid are squished.
+    pub fn move_solid(&mut self, solid_uuid: Uuid, distance: Vec2) -> Result<(), PhysicsError> {
+        self.move_solid_with_callback(solid_uuid, distance, CollisionCallback::Squish)
+    }
 
-        Err(format!("No Solid with UUID: {}", solid_uuid))
+    /// Moves a solid, running the callback for every pushed or carried actor that gets blocked by another solid.
+    pub fn move_solid_with_callback(&mut self, solid_uuid: Uuid, distance: Vec2, callback: CollisionCallback) -> Result<(), PhysicsError> {
+        match self.solid_storage.handle_of(solid_uuid) {
+            Some(handle) => self.move_solid_by_handle(handle, distance, callback),
+            None => Err(self.solid_storage.missing_error(solid_uuid))
+        }
     }
 
-    pub fn move_actor(&mut self, actor_uuid: Uuid, distance: Vec2) -> Result<(), String> {
-        for actor in self.actor_storage.actors.iter_mut() {
-            if actor.uuid == actor_uuid {
-                actor.move_actor(distance, CollisionCallback::None, &self.solid_storage.solids);
-                return Ok(())
-            }
+    pub fn move_solid_by_handle(&mut self, handle: SolidHandle, distance: Vec2, mut callback: CollisionCallback) -> Result<(), PhysicsError> {
+        if !self.riding_detected {
+            self.detect_riding();
         }
 
-        Err(format!("No Actor with UUID: {}", actor_uuid))
+        let solid = match self.solid_storage.get_mut(handle) {
+            Some(solid) => solid,
+            None => return Err(PhysicsError::StaleSolidHandle(handle))
+        };
+        let y_interactions = solid.move_y(distance.y, &self.actor_storage);
+        self.solid_storage.sync(handle);
+        self.handle_interactions(&y_interactions, handle, &mut callback);
+
+        let solid = self.solid_storage.get_mut(handle).expect("solid removed while moving");
+        let x_interactions = solid.move_x(distance.x, &self.actor_storage);
+        self.solid_storage.sync(handle);
+        self.handle_interactions(&x_interactions, handle, &mut callback);
+
+        Ok(())
     }
 
-    pub fn check_overlapping_solid(&mut self, actor_uuid: Uuid, check_offset: Vec2I32) -> Result<bool, String> {
-        let actor = self.actor_storage.get_actor(actor_uuid)?;
+    /// Moves an actor, stopping at the first solid on each axis, and reports what it hit.
+    pub fn move_actor(&mut self, actor_uuid: Uuid, distance: Vec2) -> Result<MoveResult, PhysicsError> {
+        self.move_actor_with_callback(actor_uuid, distance, CollisionCallback::None)
+    }
 
-        for solid in self.solid_storage.solids.iter() {
-            if actor.collider.is_overlapping(check_offset, &solid.collider) {
-                return Ok(true)
+    /// Moves an actor, running the callback whenever a solid blocks it.
+    pub fn move_actor_with_callback(&mut self, actor_uuid: Uuid, distance: Vec2, callback: CollisionCallback) -> Result<MoveResult, PhysicsError> {
+        match self.actor_storage.handle_of(actor_uuid) {
+            Some(handle) => self.move_actor_by_handle(handle, distance, callback),
+            None => Err(self.actor_storage.missing_error(actor_uuid))
+        }
+    }
+
+    pub fn move_actor_by_handle(&mut self, handle: ActorHandle, distance: Vec2, mut callback: CollisionCallback) -> Result<MoveResult, PhysicsError> {
+        match self.actor_storage.get_mut(handle) {
+            Some(actor) => {
+                let actor_uuid = actor.uuid;
+                let move_amount = actor.take_whole_pixels(distance);
+                let result = self.move_actor_exact(handle, move_amount, &mut callback, None);
+
+                for collision in [&result.x.hit, &result.y.hit].into_iter().flatten() {
+                    self.events.push(PhysicsEvent::ActorBlocked { actor_uuid, collision: collision.clone() });
+                }
+                Ok(result)
             }
+            None => Err(PhysicsError::StaleActorHandle(handle))
         }
+    }
 
-        Ok(false)
+    pub fn check_overlapping_solid(&mut self, actor_uuid: Uuid, check_offset: Vec2I32) -> Result<bool, PhysicsError> {
+        Ok(!self.get_overlapping_solids(actor_uuid, check_offset)?.is_empty())
     }
 
-    pub fn get_overlapping_solids(&mut self, actor_uuid: Uuid, check_offset: Vec2I32) -> Result<Vec<Uuid>, String> {
+    pub fn get_overlapping_solids(&mut self, actor_uuid: Uuid, check_offset: Vec2I32) -> Result<Vec<Uuid>, PhysicsError> {
         let actor = self.actor_storage.get_actor(actor_uuid)?;
         let mut colliding_uuids = vec![];
 
-        for solid in self.solid_storage.solids.iter() {
-            if actor.collider.is_overlapping(check_offset, &solid.collider) {
+        for solid_handle in self.solid_storage.query(&actor.collider.swept(check_offset)) {
+            let solid = self.solid_storage.get(solid_handle).expect("broadphase returned a removed solid");
+            if solid.is_overlapping(check_offset, &actor.collider) && solid.blocks(&actor.collider, check_offset) && actor.solid_filter().allows(solid) {
                 colliding_uuids.push(solid.uuid);
             }
         }
@@ -141,54 +454,322 @@ impl PhysicsEngine {
         Ok(colliding_uuids)
     }
 
-    pub fn ride(&mut self, actor_uuid: Uuid, solid_uuid: Uuid) -> Result<(), String> {
-        let actor = self.actor_storage.get_actor(actor_uuid)?;
-        actor.ride(solid_uuid);
-        Ok(())
+    pub fn ride(&mut self, actor_uuid: Uuid, solid_uuid: Uuid) -> Result<(), PhysicsError> {
+        match self.actor_storage.handle_of(actor_uuid) {
+            Some(handle) => {
+                self.actor_storage.ride(handle, solid_uuid);
+                Ok(())
+            }
+            None => Err(self.actor_storage.missing_error(actor_uuid))
+        }
+    }
+
+    /// Makes every actor ride the solids its ride mode asks for. Runs automatically before the first solid moves
+    /// each frame, or in [`PhysicsEngine::end_update`] when none did, so it only needs calling by hand when actors
+    /// move onto platforms after solids moved.
+    pub fn detect_riding(&mut self) {
+        self.riding_detected = true;
+
+        let mut rides = vec![];
+        for (actor_handle, actor) in self.actor_storage.iter_with_handles() {
+            if matches!(actor.ride_mode, RideMode::None) { continue };
+
+            // Everything the actor can ride is within one pixel of it.
+            let area = Collider::new(actor.collider.x - 1, actor.collider.y - 1, actor.collider.width + 2, actor.collider.height + 2);
+            for solid_handle in self.solid_storage.query(&area) {
+                let solid = self.solid_storage.get(solid_handle).expect("broadphase returned a removed solid");
+                if actor.wants_to_ride(solid) {
+                    rides.push((actor_handle, solid.uuid));
+                }
+            }
+        }
+
+        for (actor_handle, solid_uuid) in rides {
+            self.actor_storage.ride(actor_handle, solid_uuid);
+        }
     }
 
-    pub fn check_squished(&mut self, actor_uuid: Uuid) -> Result<bool, String> {
+    pub fn check_squished(&mut self, actor_uuid: Uuid) -> Result<bool, PhysicsError> {
         Ok(self.actor_storage.get_actor(actor_uuid)?.squished)
     }
 
+    /// Returns every event since the last call, oldest first. Call this once per frame, otherwise events pile up.
+    pub fn drain_events(&mut self) -> std::vec::Drain<'_, PhysicsEvent> {
+        self.events.drain(..)
+    }
+
     /// Must be run at end of function to clean up the engine.
     pub fn end_update(&mut self) {
-        for actor in self.actor_storage.actors.iter_mut() {
+        // Without any solid moving nothing looked for riders yet, and every actor would seem to stop riding.
+        if !self.riding_detected {
+            self.detect_riding();
+        }
+
+        for actor in self.actor_storage.iter_mut() {
             actor.update();
         }
+        // Colliders edited directly since the last frame are found by brute force until this.
+        self.sync_broadphase();
+
+        let riding = self.actor_storage.riding_pairs();
+        for (actor_uuid, solid_uuid) in riding.difference(&self.riding_last_frame) {
+            self.events.push(PhysicsEvent::ActorStartedRiding { actor_uuid: *actor_uuid, solid_uuid: *solid_uuid });
+        }
+        for (actor_uuid, solid_uuid) in self.riding_last_frame.difference(&riding) {
+            self.events.push(PhysicsEvent::ActorStoppedRiding { actor_uuid: *actor_uuid, solid_uuid: *solid_uuid });
+        }
+        self.riding_last_frame = riding;
+
+        let inside = self.trigger_overlaps();
+        for (actor_uuid, trigger_uuid) in inside.difference(&self.inside_last_frame) {
+            self.events.push(PhysicsEvent::TriggerEntered { actor_uuid: *actor_uuid, trigger_uuid: *trigger_uuid });
+        }
+        for (actor_uuid, trigger_uuid) in inside.intersection(&self.inside_last_frame) {
+            self.events.push(PhysicsEvent::TriggerStayed { actor_uuid: *actor_uuid, trigger_uuid: *trigger_uuid });
+        }
+        for (actor_uuid, trigger_uuid) in self.inside_last_frame.difference(&inside) {
+            self.events.push(PhysicsEvent::TriggerExited { actor_uuid: *actor_uuid, trigger_uuid: *trigger_uuid });
+        }
+        self.inside_last_frame = inside;
+
+        self.actor_storage.clear_riding();
+        self.riding_detected = false;
+    }
+
+    /// Despawns an actor and returns it. Its handle and UUID stop resolving.
+    pub fn despawn_actor(&mut self, actor_uuid: Uuid) -> Result<Actor, PhysicsError> {
+        self.actor_storage.remove(actor_uuid)
+    }
+
+    /// Despawns a solid and returns it. Actors riding the solid stop riding it.
+    pub fn despawn_solid(&mut self, solid_uuid: Uuid) -> Result<Solid, PhysicsError> {
+        let solid = self.solid_storage.remove(solid_uuid)?;
+        self.actor_storage.stop_riding_solid(solid_uuid);
+
+        Ok(solid)
+    }
+
+    /// Despawns every solid with the given tag and returns their ids.
+    pub fn despawn_solids_with_tag(&mut self, tag: &str) -> Vec<Uuid> {
+        let solids = self.solid_storage.get_solids_with_tag(tag);
+
+        for solid_uuid in solids.iter() {
+            self.solid_storage.remove(*solid_uuid).expect("solid with tag was not stored");
+            self.actor_storage.stop_riding_solid(*solid_uuid);
+        }
+
+        solids
+    }
+
+    /// Despawns a trigger and returns it. Actors inside it get an exit event at the next end_update.
+    pub fn despawn_trigger(&mut self, trigger_uuid: Uuid) -> Result<Trigger, PhysicsError> {
+        self.trigger_storage.remove(trigger_uuid)
+    }
+
+    /// Despawns every trigger with the given tag and returns their ids.
+    pub fn despawn_triggers_with_tag(&mut self, tag: &str) -> Vec<Uuid> {
+        let triggers = self.trigger_storage.get_triggers_with_tag(tag);
+
+        for trigger_uuid in triggers.iter() {
+            self.trigger_storage.remove(*trigger_uuid).expect("trigger with tag was not stored");
+        }
+
+        triggers
+    }
+
+    /// Forgets every despawned UUID, so they are reported as not found instead of despawned.
+    /// Each despawn remembers its UUID until this is called or the storage is cleared.
+    pub fn forget_despawned(&mut self) {
+        self.actor_storage.forget_despawned();
+        self.solid_storage.forget_despawned();
+        self.trigger_storage.forget_despawned();
+    }
+
+    /// Moves a trigger. Triggers never push or carry actors, and actors entering or leaving it are reported at end_update.
+    pub fn move_trigger(&mut self, trigger_uuid: Uuid, distance: Vec2) -> Result<(), PhysicsError> {
+        match self.trigger_storage.handle_of(trigger_uuid) {
+            Some(handle) => self.move_trigger_by_handle(handle, distance),
+            None => Err(self.trigger_storage.missing_error(trigger_uuid))
+        }
+    }
+
+    pub fn move_trigger_by_handle(&mut self, handle: TriggerHandle, distance: Vec2) -> Result<(), PhysicsError> {
+        match self.trigger_storage.get_mut(handle) {
+            Some(trigger) => trigger.move_by(distance),
+            None => return Err(PhysicsError::StaleTriggerHandle(handle))
+        }
+        self.trigger_storage.sync(handle);
+
+        Ok(())
+    }
+
+    /// Returns the handle of the trigger with the given UUID.
+    pub fn trigger_handle(&self, trigger_uuid: Uuid) -> Option<TriggerHandle> {
+        self.trigger_storage.handle_of(trigger_uuid)
+    }
+
+    /// Returns every trigger the actor overlaps right now, rather than at the last end_update.
+    pub fn get_overlapping_triggers(&mut self, actor_uuid: Uuid) -> Result<Vec<Uuid>, PhysicsError> {
+        let actor = self.actor_storage.get_actor(actor_uuid)?;
+        let (collider, layers) = (actor.collider.clone(), actor.layers);
+
+        Ok(self.trigger_storage.query(&collider).into_iter()
+            .filter_map(|handle| self.trigger_storage.get(handle))
+            .filter(|trigger| trigger.layers.collides_with(&layers) && trigger.collider.is_overlapping(vec2i32(0, 0), &collider))
+            .map(|trigger| trigger.uuid)
+            .collect())
+    }
+
+    /// Returns every actor inside the trigger right now.
+    pub fn get_actors_in_trigger(&mut self, trigger_uuid: Uuid) -> Result<Vec<Uuid>, PhysicsError> {
+        let trigger = self.trigger_storage.get_trigger(trigger_uuid)?;
+        let (collider, layers) = (trigger.collider.clone(), trigger.layers);
+
+        Ok(self.actor_storage.query(&collider).into_iter()
+            .filter_map(|handle| self.actor_storage.get(handle))
+            .filter(|actor| actor.layers.collides_with(&layers) && actor.collider.is_overlapping(vec2i32(0, 0), &collider))
+            .map(|actor| actor.uuid)
+            .collect())
+    }
+
+    /// Every (actor, trigger) pair that overlaps.
+    fn trigger_overlaps(&self) -> BTreeSet<(Uuid, Uuid)> {
+        let mut inside = BTreeSet::new();
+
+        for trigger in self.trigger_storage.iter() {
+            for handle in self.actor_storage.query(&trigger.collider) {
+                let actor = self.actor_storage.get(handle).expect("broadphase returned a removed actor");
+                if actor.layers.collides_with(&trigger.layers) && actor.collider.is_overlapping(vec2i32(0, 0), &trigger.collider) {
+                    inside.insert((actor.uuid, trigger.uuid));
+                }
+            }
+        }
+
+        inside
+    }
+
+    /// Lets the actor fall through the one way solids it stands on for the given number of frames.
+    /// Returns the solids it drops through.
+    pub fn drop_through(&mut self, actor_uuid: Uuid, frames: u32) -> Result<Vec<Uuid>, PhysicsError> {
+        let down = self.actor_storage.get_actor(actor_uuid)?.down;
+        let platforms: Vec<Uuid> = self.get_overlapping_solids(actor_uuid, down)?.into_iter()
+            .filter(|solid_uuid| self.solid_storage.get_solid(*solid_uuid).is_ok_and(|solid| solid.one_way.is_some()))
+            .collect();
+
+        let actor = self.actor_storage.get_actor(actor_uuid)?;
+        actor.dropping_through = platforms.clone();
+        actor.drop_through_frames = frames;
+
+        Ok(platforms)
+    }
+
+    /// Spawns a one way solid that only blocks movement into the face with the given normal, and returns its id.
+    pub fn spawn_one_way_solid(&mut self, solid_collider: Collider, normal: Vec2I32, tags: Option<Vec<String>>) -> Uuid {
+        let uuid = self.spawn_solid(solid_collider, tags);
+        self.solid_storage.get_solid(uuid).expect("solid was just spawned").one_way = Some(normal);
+        uuid
+    }
+
+    /// Spawns a slope filling the collider, see [`Slope`], and returns its id.
+    pub fn spawn_slope(&mut self, solid_collider: Collider, slope: Slope, tags: Option<Vec<String>>) -> Uuid {
+        let uuid = self.spawn_solid(solid_collider, tags);
+        self.solid_storage.get_solid(uuid).expect("solid was just spawned").slope = Some(slope);
+        uuid
+    }
+
+    /// Spawns a tile grid with tile (0, 0) at the position, see [`TileGrid`], and returns its id.
+    pub fn spawn_tile_grid(&mut self, x: i32, y: i32, grid: TileGrid, tags: Option<Vec<String>>) -> Uuid {
+        let uuid = self.spawn_solid(grid.bounds(x, y), tags);
+        self.solid_storage.get_solid(uuid).expect("solid was just spawned").tiles = Some(grid);
+        uuid
+    }
+
+    /// Replaces a tile of a tile grid. Actors inside the new tile are not pushed out.
+    pub fn set_tile(&mut self, grid_uuid: Uuid, column: i32, row: i32, tile: Tile) -> Result<(), PhysicsError> {
+        match &mut self.solid_storage.get_solid(grid_uuid)?.tiles {
+            Some(grid) => grid.set(column, row, tile),
+            None => Err(PhysicsError::NotATileGrid(grid_uuid))
+        }
+    }
+
+    pub fn clear_tile(&mut self, grid_uuid: Uuid, column: i32, row: i32) -> Result<(), PhysicsError> {
+        self.set_tile(grid_uuid, column, row, Tile::Empty)
+    }
+
+    /// Returns the tile, or None outside of the grid.
+    pub fn get_tile(&mut self, grid_uuid: Uuid, column: i32, row: i32) -> Result<Option<Tile>, PhysicsError> {
+        match &self.solid_storage.get_solid(grid_uuid)?.tiles {
+            Some(grid) => Ok(grid.get(column, row)),
+            None => Err(PhysicsError::NotATileGrid(grid_uuid))
+        }
     }
 
     /// Spawns a solid and returns the id of the solid
     pub fn spawn_solid(&mut self, solid_collider: Collider, tags: Option<Vec<String>>) -> Uuid {
+        self.spawn_solid_with_layers(solid_collider, tags, CollisionLayers::DEFAULT)
+    }
+
+    /// Spawns a solid on the given layers and returns the id of the solid
+    pub fn spawn_solid_with_layers(&mut self, solid_collider: Collider, tags: Option<Vec<String>>, layers: CollisionLayers) -> Uuid {
+        let mut uuid;
+
+        loop {
+            uuid = Uuid::new_v4();
+            if self.solid_storage.handle_of(uuid).is_none() {
+                break;
+            }
+        }
+
+        let mut solid = Solid::new(solid_collider, uuid, tags);
+        solid.layers = layers;
+        self.solid_storage.insert(solid).expect("freshly generated uuid was taken");
+
+        uuid
+    }
+
+    /// Spawns a trigger and returns the id of the trigger
+    pub fn spawn_trigger(&mut self, trigger_collider: Collider, tags: Option<Vec<String>>) -> Uuid {
+        self.spawn_trigger_with_layers(trigger_collider, tags, CollisionLayers::DEFAULT)
+    }
+
+    /// Spawns a trigger that only reports actors on the given layers and returns the id of the trigger
+    pub fn spawn_trigger_with_layers(&mut self, trigger_collider: Collider, tags: Option<Vec<String>>, layers: CollisionLayers) -> Uuid {
         let mut uuid;
 
         loop {
             uuid = Uuid::new_v4();
-            match self.solid_storage.get_solid(uuid) {
-                Ok(_) => { }
-                Err(_) => { break; }
+            if self.trigger_storage.handle_of(uuid).is_none() {
+                break;
             }
         }
 
-        self.solid_storage.solids.push(Solid::new(solid_collider, uuid, tags));
+        let mut trigger = Trigger::new(trigger_collider, uuid, tags);
+        trigger.layers = layers;
+        self.trigger_storage.insert(trigger).expect("freshly generated uuid was taken");
 
         uuid
     }
 
     /// Spawns an actor and returns the id of the actor
     pub fn spawn_actor(&mut self, actor_collider: Collider) -> Uuid {
+        self.spawn_actor_with_layers(actor_collider, CollisionLayers::DEFAULT)
+    }
+
+    /// Spawns an actor on the given layers and returns the id of the actor
+    pub fn spawn_actor_with_layers(&mut self, actor_collider: Collider, layers: CollisionLayers) -> Uuid {
         let mut uuid;
 
         loop {
             uuid = Uuid::new_v4();
-            match self.actor_storage.get_actor(uuid) {
-                Ok(_) => { }
-                Err(_) => { break; }
+            if self.actor_storage.handle_of(uuid).is_none() {
+                break;
             }
         }
 
-        self.actor_storage.actors.push(Actor::new(actor_collider, uuid));
+        let mut actor = Actor::new(actor_collider, uuid);
+        actor.layers = layers;
+        self.actor_storage.insert(actor).expect("freshly generated uuid was taken");
 
         uuid
     }
-}
\ No newline at end of file
+}
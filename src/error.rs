@@ -0,0 +1,97 @@
+use std::fmt::{Display, Formatter};
+use uuid::Uuid;
+use crate::engine::{ActorHandle, SolidHandle, TriggerHandle};
+
+/// Every error the engine can return.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PhysicsError {
+    /// No actor with this UUID was ever spawned.
+    ActorNotFound(Uuid),
+    /// No solid with this UUID was ever spawned.
+    SolidNotFound(Uuid),
+    /// No trigger with this UUID was ever spawned.
+    TriggerNotFound(Uuid),
+    /// The actor with this UUID existed but has been despawned.
+    ActorDespawned(Uuid),
+    /// The solid with this UUID existed but has been despawned.
+    SolidDespawned(Uuid),
+    /// The trigger with this UUID existed but has been despawned.
+    TriggerDespawned(Uuid),
+    /// The actor this handle pointed at has been despawned.
+    StaleActorHandle(ActorHandle),
+    /// The solid this handle pointed at has been despawned.
+    StaleSolidHandle(SolidHandle),
+    /// The trigger this handle pointed at has been despawned.
+    StaleTriggerHandle(TriggerHandle),
+    /// An actor with this UUID already exists.
+    DuplicateActor(Uuid),
+    /// A solid with this UUID already exists.
+    DuplicateSolid(Uuid),
+    /// A trigger with this UUID already exists.
+    DuplicateTrigger(Uuid),
+    /// The solid with this UUID is not a tile grid.
+    NotATileGrid(Uuid),
+    /// The tile lies outside of its grid.
+    TileOutOfBounds { column: i32, row: i32 },
+    /// A level file could not be read or written.
+    LevelIo { path: String, message: String },
+    /// A level file was not valid level json. Line and column come from serde_json and are 1-based.
+    LevelParse { line: usize, column: usize, message: String },
+    /// A level could not be turned into json.
+    LevelSerialize(String),
+    /// A level file was written by a newer version of the level format than this crate supports.
+    UnsupportedLevelVersion { found: u64, supported: u32 },
+    /// A level file was valid json but did not have the expected fields or types.
+    LevelSchema(String),
+    /// A level file had a field the level format doesn't know, only an error when loading strictly.
+    UnknownLevelField(String),
+    /// Something was wrong with one entry of a level file. The third solid is at section `level_solids`, index 2.
+    LevelEntry { section: String, index: usize, error: Box<PhysicsError> },
+    /// A level file contained a UUID that could not be parsed.
+    InvalidUuid { value: String, source: uuid::Error },
+    /// A level file contained a tile grid with an unknown tile character.
+    InvalidTile { value: char, column: i32, row: i32 },
+    /// A level file contained a tile grid whose tiles are not at least a pixel wide.
+    InvalidTileSize(i32)
+}
+
+impl Display for PhysicsError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PhysicsError::ActorNotFound(uuid) => write!(f, "Expected an actor with uuid {}, but did not find one.", uuid),
+            PhysicsError::SolidNotFound(uuid) => write!(f, "Expected a solid with uuid {}, but did not find one.", uuid),
+            PhysicsError::TriggerNotFound(uuid) => write!(f, "Expected a trigger with uuid {}, but did not find one.", uuid),
+            PhysicsError::ActorDespawned(uuid) => write!(f, "Actor with uuid {} was despawned.", uuid),
+            PhysicsError::SolidDespawned(uuid) => write!(f, "Solid with uuid {} was despawned.", uuid),
+            PhysicsError::TriggerDespawned(uuid) => write!(f, "Trigger with uuid {} was despawned.", uuid),
+            PhysicsError::StaleActorHandle(handle) => write!(f, "Actor with handle {:?} was despawned.", handle),
+            PhysicsError::StaleSolidHandle(handle) => write!(f, "Solid with handle {:?} was despawned.", handle),
+            PhysicsError::StaleTriggerHandle(handle) => write!(f, "Trigger with handle {:?} was despawned.", handle),
+            PhysicsError::DuplicateActor(uuid) => write!(f, "An actor with uuid {} already exists.", uuid),
+            PhysicsError::DuplicateSolid(uuid) => write!(f, "A solid with uuid {} already exists.", uuid),
+            PhysicsError::DuplicateTrigger(uuid) => write!(f, "A trigger with uuid {} already exists.", uuid),
+            PhysicsError::NotATileGrid(uuid) => write!(f, "Solid with uuid {} is not a tile grid.", uuid),
+            PhysicsError::TileOutOfBounds { column, row } => write!(f, "Tile at column {}, row {} is outside of the grid.", column, row),
+            PhysicsError::LevelIo { path, message } => write!(f, "Could not access level file {}: {}", path, message),
+            PhysicsError::LevelParse { line, column, message } => write!(f, "Level did not have the correct formatting at line {}, column {}: {}", line, column, message),
+            PhysicsError::LevelSerialize(message) => write!(f, "Could not write level as json: {}", message),
+            PhysicsError::UnsupportedLevelVersion { found, supported } => write!(f, "Level has format version {}, but only versions up to {} are supported.", found, supported),
+            PhysicsError::LevelSchema(message) => write!(f, "Level did not have the expected fields: {}", message),
+            PhysicsError::UnknownLevelField(field) => write!(f, "Level contained unknown field {:?}.", field),
+            PhysicsError::LevelEntry { section, index, error } => write!(f, "In {}[{}]: {}", section, index, error),
+            PhysicsError::InvalidUuid { value, source } => write!(f, "Level contained invalid uuid {:?}: {}", value, source),
+            PhysicsError::InvalidTile { value, column, row } => write!(f, "Level contained invalid tile {:?} at column {}, row {}.", value, column, row),
+            PhysicsError::InvalidTileSize(size) => write!(f, "Level contained a tile grid with tile size {}.", size)
+        }
+    }
+}
+
+impl std::error::Error for PhysicsError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            PhysicsError::InvalidUuid { source, .. } => Some(source),
+            PhysicsError::LevelEntry { error, .. } => Some(error.as_ref()),
+            _ => None
+        }
+    }
+}
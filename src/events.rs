@@ -0,0 +1,27 @@
+use uuid::Uuid;
+use crate::actor::CollisionInfo;
+use crate::math::Vec2I32;
+
+/// Something that happened inside the engine. Collected by [`crate::engine::PhysicsEngine`] until
+/// [`crate::engine::PhysicsEngine::drain_events`] is called, which should happen once per frame.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PhysicsEvent {
+    /// A solid stopped an actor's move, or stopped an actor being pushed without squishing it.
+    ActorBlocked { actor_uuid: Uuid, collision: CollisionInfo },
+    /// A moving solid pushed or carried an actor by the given motion.
+    ActorPushed { actor_uuid: Uuid, solid_uuid: Uuid, motion: Vec2I32 },
+    /// An actor moving through the engine pushed an actor that blocks actors by the given motion.
+    ActorPushedByActor { actor_uuid: Uuid, pusher_uuid: Uuid, motion: Vec2I32 },
+    /// A moving solid pushed an actor into another solid and squished it.
+    ActorSquished { actor_uuid: Uuid, solid_uuid: Uuid, collision: CollisionInfo },
+    /// The actor rides a solid it did not ride last frame.
+    ActorStartedRiding { actor_uuid: Uuid, solid_uuid: Uuid },
+    /// The actor no longer rides a solid it rode last frame.
+    ActorStoppedRiding { actor_uuid: Uuid, solid_uuid: Uuid },
+    /// The actor started overlapping a trigger.
+    TriggerEntered { actor_uuid: Uuid, trigger_uuid: Uuid },
+    /// The actor overlapped the trigger last frame and still does.
+    TriggerStayed { actor_uuid: Uuid, trigger_uuid: Uuid },
+    /// The actor stopped overlapping a trigger, or one of them was despawned.
+    TriggerExited { actor_uuid: Uuid, trigger_uuid: Uuid }
+}
@@ -1,14 +1,46 @@
+use std::collections::HashSet;
+use std::fmt::Formatter;
+use std::fs::File;
+use std::marker::PhantomData;
+use std::io::{BufWriter, Read, Write};
 use std::str::FromStr;
+#[cfg(feature = "macroquad")]
 use macroquad::file::{load_string};
+use crate::actor::Actor;
 use crate::engine::PhysicsEngine;
+use crate::error::PhysicsError;
+use crate::layers::CollisionLayers;
 use serde::{Serialize, Deserialize};
+use serde::de::{DeserializeOwned, DeserializeSeed, Deserializer, IgnoredAny, MapAccess, SeqAccess, Visitor};
+use serde_json::{Map, Value};
 use uuid::Uuid;
+use crate::math::vec2i32;
 use crate::prelude::Collider;
+use crate::slope::Slope;
 use crate::solid::Solid;
+use crate::tile_grid::{Tile, TileGrid};
+use crate::trigger::Trigger;
+
+/// The level format version written by this crate. Files without a version predate it and count as version 0.
+pub const LEVEL_FORMAT_VERSION: u32 = 1;
+
+/// Upgrades a level document from the version at its index to the next one, so older levels keep loading.
+const MIGRATIONS: [fn(&mut Map<String, Value>); LEVEL_FORMAT_VERSION as usize] = [
+    migrate_from_unversioned
+];
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct JsonLevel {
-    level_solids: Vec<JsonCollider>
+    #[serde(default)]
+    format_version: u32,
+    level_solids: Vec<JsonCollider>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    level_triggers: Vec<JsonCollider>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    level_tile_grids: Vec<JsonTileGrid>,
+    /// Actors have no tags, so theirs are always left out.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    level_actors: Vec<JsonCollider>
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -17,60 +49,607 @@ pub struct JsonCollider {
     y: i32,
     width: i32,
     height: i32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    tags: Option<Vec<String>>,
+    uuid: Option<String>,
+    /// Only written for disabled colliders.
+    #[serde(default = "enabled", skip_serializing_if = "is_enabled")]
+    enabled: bool,
+    /// Collision layers, left out when they are the defaults.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    layer: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    mask: Option<u32>,
+    /// The normal of the face a one way solid blocks.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    one_way: Option<[i32; 2]>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    slope: Option<JsonSlope>,
+    /// Fields this version of the format doesn't know.
+    #[serde(flatten)]
+    unknown: Map<String, Value>
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JsonSlope {
+    UpRight,
+    UpLeft
+}
+
+impl JsonCollider {
+    fn new(collider: &Collider, tags: Option<Vec<String>>, uuid: Option<Uuid>, layers: CollisionLayers) -> Self {
+        Self {
+            x: collider.x,
+            y: collider.y,
+            width: collider.width,
+            height: collider.height,
+            tags,
+            uuid: uuid.map(|uuid| uuid.hyphenated().to_string()),
+            enabled: collider.enabled,
+            layer: json_layer(layers.layer, CollisionLayers::DEFAULT.layer),
+            mask: json_layer(layers.mask, CollisionLayers::DEFAULT.mask),
+            one_way: None,
+            slope: None,
+            unknown: Map::new()
+        }
+    }
+
+    fn from_solid(solid: &Solid, save_uuid: bool) -> Self {
+        Self {
+            one_way: solid.one_way.map(|normal| [normal.x, normal.y]),
+            slope: solid.slope.map(|slope| match slope {
+                Slope::UpRight => JsonSlope::UpRight,
+                Slope::UpLeft => JsonSlope::UpLeft
+            }),
+            ..Self::new(&solid.collider, Some(solid.tags.clone()), save_uuid.then_some(solid.uuid), solid.layers)
+        }
+    }
+
+    fn collider(&self) -> Collider {
+        Collider {
+            enabled: self.enabled,
+            ..Collider::new(self.x, self.y, self.width, self.height)
+        }
+    }
+
+    fn layers(&self) -> CollisionLayers {
+        CollisionLayers::new(self.layer.unwrap_or(CollisionLayers::DEFAULT.layer), self.mask.unwrap_or(CollisionLayers::DEFAULT.mask))
+    }
+
+    /// Gives the solid the shape stored for it.
+    fn shape(&self, solid: &mut Solid) {
+        solid.layers = self.layers();
+        solid.one_way = self.one_way.map(|[x, y]| vec2i32(x, y));
+        solid.slope = self.slope.map(|slope| match slope {
+            JsonSlope::UpRight => Slope::UpRight,
+            JsonSlope::UpLeft => Slope::UpLeft
+        });
+    }
+}
+
+/// A tile grid stored as one string per row, top row first. Each character is a tile:
+/// `.` empty, `#` solid, `-` one way, `/` a slope going up to the right and `\\` one going up to the left.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct JsonTileGrid {
+    x: i32,
+    y: i32,
+    tile_size: i32,
+    rows: Vec<String>,
     tags: Option<Vec<String>>,
-    uuid: Option<String>
+    uuid: Option<String>,
+    #[serde(default = "enabled", skip_serializing_if = "is_enabled")]
+    enabled: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    layer: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    mask: Option<u32>,
+    #[serde(flatten)]
+    unknown: Map<String, Value>
 }
 
-/// Loads a json level format to the engine via a path.
-pub async fn load_level(engine: &mut PhysicsEngine, path: &str, clear_solids: bool) -> Result<(), String> {
-    if clear_solids {
-        engine.solid_storage.solids.clear();
+impl JsonTileGrid {
+    fn grid(&self) -> Result<TileGrid, PhysicsError> {
+        if self.tile_size <= 0 { return Err(PhysicsError::InvalidTileSize(self.tile_size)) };
+
+        let rows = self.rows.len() as i32;
+        let columns = self.rows.iter().map(|row| row.chars().count()).max().unwrap_or(0) as i32;
+        let mut grid = TileGrid::new(self.tile_size, columns, rows);
+
+        for (line, tiles) in self.rows.iter().enumerate() {
+            // The first line is the top row.
+            let row = rows - 1 - line as i32;
+            for (column, value) in tiles.chars().enumerate() {
+                let column = column as i32;
+                let tile = match value {
+                    '.' => Tile::Empty,
+                    '#' => Tile::Solid,
+                    '-' => Tile::OneWay,
+                    '/' => Tile::Slope(Slope::UpRight),
+                    '\\' => Tile::Slope(Slope::UpLeft),
+                    _ => return Err(PhysicsError::InvalidTile { value, column, row })
+                };
+                grid.set(column, row, tile)?;
+            }
+        }
+
+        Ok(grid)
+    }
+
+    fn rows(grid: &TileGrid) -> Vec<String> {
+        (0..grid.rows()).rev()
+            .map(|row| (0..grid.columns()).map(|column| match grid.get(column, row).unwrap_or_default() {
+                Tile::Empty => '.',
+                Tile::Solid => '#',
+                Tile::OneWay => '-',
+                Tile::Slope(Slope::UpRight) => '/',
+                Tile::Slope(Slope::UpLeft) => '\\'
+            }).collect())
+            .collect()
     }
+}
+
+fn json_layer(value: u32, default: u32) -> Option<u32> {
+    (value != default).then_some(value)
+}
+
+/// Keeps the position serde_json reports so the offending part of the level can be found.
+fn parse_error(error: serde_json::Error) -> PhysicsError {
+    PhysicsError::LevelParse {
+        line: error.line(),
+        column: error.column(),
+        message: error.to_string()
+    }
+}
+
+fn parse_uuid(uuid: String) -> Result<Uuid, PhysicsError> {
+    match Uuid::from_str(uuid.as_str()) {
+        Ok(uuid) => Ok(uuid),
+        Err(error) => Err(PhysicsError::InvalidUuid { value: uuid, source: error })
+    }
+}
+
+fn enabled() -> bool {
+    true
+}
+
+fn is_enabled(enabled: &bool) -> bool {
+    *enabled
+}
+
+/// How [`load_level_with`] adds a level to the engine.
+#[derive(Debug, Clone, Default)]
+pub struct LoadOptions {
+    /// Removes every solid and trigger before loading.
+    pub clear_solids: bool,
+    /// Removes every actor before loading, for levels saved with their actors.
+    pub clear_actors: bool,
+    /// Runs [`PhysicsEngine::merge_static_solids`] once the level is loaded, which also merges solids that were already spawned.
+    pub merge_solids: bool,
+    /// Fails on fields the level format doesn't know, instead of returning them as warnings.
+    pub strict: bool
+}
 
+/// Loads a json level format to the engine via a path. Clearing the solids clears the level's triggers too.
+/// Tile grids become solids with [`Solid::tiles`] set. Unknown fields are ignored, use [`load_level_with`] to see them.
+/// Without the `macroquad` feature, use [`load_level_from_file`] instead.
+#[cfg(feature = "macroquad")]
+pub async fn load_level(engine: &mut PhysicsEngine, path: &str, clear_solids: bool) -> Result<(), PhysicsError> {
+    load_level_with(engine, path, LoadOptions { clear_solids, ..Default::default() }).await.map(|_| ())
+}
+
+/// Loads a json level format to the engine via a path, see [`LoadOptions`].
+/// Returns a [`PhysicsError::UnknownLevelField`] warning for every field that was ignored.
+/// Goes through macroquad's file loading, so it also works on the web.
+#[cfg(feature = "macroquad")]
+pub async fn load_level_with(engine: &mut PhysicsEngine, path: &str, options: LoadOptions) -> Result<Vec<PhysicsError>, PhysicsError> {
     let file = match load_string(path).await {
         Ok(file) => { file },
-        Err(_) => { return Err(format!("Could not load file with name {path}")); }
+        Err(error) => { return Err(PhysicsError::LevelIo { path: path.to_string(), message: error.kind.to_string() }); }
+    };
+
+    load_level_from_str(engine, file.as_str(), options)
+}
+
+/// Like [`load_level_with`], but reads the file right away with `std::fs`.
+pub fn load_level_from_file(engine: &mut PhysicsEngine, path: &str, options: LoadOptions) -> Result<Vec<PhysicsError>, PhysicsError> {
+    let file = match std::fs::read_to_string(path) {
+        Ok(file) => { file },
+        Err(error) => { return Err(PhysicsError::LevelIo { path: path.to_string(), message: error.to_string() }); }
+    };
+
+    load_level_from_str(engine, file.as_str(), options)
+}
+
+/// Like [`load_level_with`], but reads the level json from any reader.
+pub fn load_level_from_reader(engine: &mut PhysicsEngine, mut reader: impl Read, options: LoadOptions) -> Result<Vec<PhysicsError>, PhysicsError> {
+    let mut json = String::new();
+    if let Err(error) = reader.read_to_string(&mut json) {
+        return Err(PhysicsError::LevelIo { path: "<reader>".to_string(), message: error.to_string() });
+    }
+
+    load_level_from_str(engine, json.as_str(), options)
+}
+
+/// Loads a json level that is already in memory, see [`load_level_with`].
+/// Older format versions are upgraded first. Every entry is checked before anything is cleared or added,
+/// so nothing is changed in the engine if the level can't be loaded.
+pub fn load_level_from_str(engine: &mut PhysicsEngine, json: &str, options: LoadOptions) -> Result<Vec<PhysicsError>, PhysicsError> {
+    let document = match serde_json::from_str::<Value>(json) {
+        Ok(document) => { document }
+        Err(error) => { return Err(parse_error(error)) }
+    };
+    let (level, warnings) = read_level(document, json, options.strict)?;
+    let entities = prepare_level(engine, level, &options)?;
+
+    if options.clear_solids {
+        engine.solid_storage.clear();
+        engine.trigger_storage.clear();
+    }
+    if options.clear_actors {
+        engine.actor_storage.clear();
+    }
+
+    for solid in entities.solids {
+        engine.solid_storage.insert(solid).expect("level entries were checked for duplicates");
+    }
+    for trigger in entities.triggers {
+        engine.trigger_storage.insert(trigger).expect("level entries were checked for duplicates");
+    }
+    for actor in entities.actors {
+        engine.actor_storage.insert(actor).expect("level entries were checked for duplicates");
+    }
+
+    if options.merge_solids {
+        engine.merge_static_solids();
+    }
+
+    Ok(warnings)
+}
+
+/// Upgrades the document to the current format version and reads every entry in it,
+/// along with the unknown fields found on the way.
+/// `json` is the text the document came from, used to find where a broken entry is.
+fn read_level(document: Value, json: &str, strict: bool) -> Result<(JsonLevel, Vec<PhysicsError>), PhysicsError> {
+    let Value::Object(mut document) = document else {
+        return Err(PhysicsError::LevelSchema("a level has to be a json object".to_string()));
     };
+    migrate(&mut document)?;
+    document.remove("format_version");
 
-    let level = match serde_json::from_str::<JsonLevel>(file.as_str()) {
-        Ok(level) => { level }
-        Err(_) => { return Err(format!("File {path} did not have the correct formatting to become a level.")) }
+    let level = JsonLevel {
+        format_version: LEVEL_FORMAT_VERSION,
+        level_solids: entries(&mut document, json, "level_solids")?,
+        level_triggers: entries(&mut document, json, "level_triggers")?,
+        level_tile_grids: entries(&mut document, json, "level_tile_grids")?,
+        level_actors: entries(&mut document, json, "level_actors")?
     };
 
-    for collider in level.level_solids {
-        match collider.uuid {
-            Some(uuid) => { engine.solid_storage.solids.push(Solid::new(Collider::new(collider.x, collider.y, collider.width, collider.height), match Uuid::from_str(uuid.as_str()) {
-                Ok(uuid) => uuid,
-                Err(error) => {return Err(format!("{}", error))}
-            }, collider.tags)) }
-            None => { engine.spawn_solid(Collider::new(collider.x, collider.y, collider.width, collider.height), collider.tags); }
+    // Everything that is left over at the top level is unknown too.
+    let mut unknown: Vec<PhysicsError> = document.keys().map(|field| PhysicsError::UnknownLevelField(field.clone())).collect();
+    unknown.extend(unknown_fields("level_solids", level.level_solids.iter().map(|entry| &entry.unknown)));
+    unknown.extend(unknown_fields("level_triggers", level.level_triggers.iter().map(|entry| &entry.unknown)));
+    unknown.extend(unknown_fields("level_tile_grids", level.level_tile_grids.iter().map(|entry| &entry.unknown)));
+    unknown.extend(unknown_fields("level_actors", level.level_actors.iter().map(|entry| &entry.unknown)));
+
+    if strict && !unknown.is_empty() {
+        return Err(unknown.remove(0));
+    }
+
+    Ok((level, unknown))
+}
+
+fn migrate(document: &mut Map<String, Value>) -> Result<(), PhysicsError> {
+    let version = match document.get("format_version") {
+        None => 0,
+        Some(value) => match value.as_u64() {
+            Some(version) => version,
+            None => return Err(PhysicsError::LevelSchema(format!("format_version has to be a whole number, not {}", value)))
         }
+    };
+    if version > LEVEL_FORMAT_VERSION as u64 {
+        return Err(PhysicsError::UnsupportedLevelVersion { found: version, supported: LEVEL_FORMAT_VERSION });
+    }
 
+    for migration in &MIGRATIONS[version as usize..] {
+        migration(document);
     }
+    document.insert("format_version".to_string(), Value::from(LEVEL_FORMAT_VERSION));
 
     Ok(())
 }
 
-/// Takes all spawned solids and prints it as a jsonified level.
-pub fn save_level(engine: &mut PhysicsEngine, save_uuid: bool) -> Result<(), String> {
+/// Levels from before versioning only had solids, and everything added since has a default.
+fn migrate_from_unversioned(document: &mut Map<String, Value>) {
+    document.entry("level_solids").or_insert_with(|| Value::Array(vec![]));
+}
+
+/// Takes a list of entries out of the document, reporting which entry didn't fit the format and where it is in the text.
+fn entries<T: DeserializeOwned>(document: &mut Map<String, Value>, json: &str, section: &str) -> Result<Vec<T>, PhysicsError> {
+    match document.remove(section) {
+        None | Some(Value::Null) => Ok(vec![]),
+        Some(Value::Array(values)) => values.into_iter().enumerate()
+            .map(|(index, value)| serde_json::from_value(value)
+                .map_err(|error| entry_error(section, index, locate_entry_error::<T>(json, section, index)
+                    .unwrap_or(PhysicsError::LevelSchema(error.to_string())))))
+            .collect(),
+        Some(_) => Err(PhysicsError::LevelSchema(format!("{} has to be a list", section)))
+    }
+}
+
+/// Reads the entry again straight from the text, skipping everything else, so that its error has a line and column.
+/// None if the text reads fine, which happens when a migration is what broke the entry.
+fn locate_entry_error<T: DeserializeOwned>(json: &str, section: &str, index: usize) -> Option<PhysicsError> {
+    let mut deserializer = serde_json::Deserializer::from_str(json);
+    let seed = EntryAt::<T> { section, index, entry: PhantomData };
+
+    seed.deserialize(&mut deserializer).err().map(parse_error)
+}
+
+/// Deserializes only the entry at the index of the section in a level document.
+struct EntryAt<'a, T> {
+    section: &'a str,
+    index: usize,
+    entry: PhantomData<T>
+}
+
+impl<'de, T: DeserializeOwned> DeserializeSeed<'de> for EntryAt<'_, T> {
+    type Value = ();
+
+    /// The level is a map and the section in it a list, so this follows whichever it finds.
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+        deserializer.deserialize_any(self)
+    }
+}
+
+impl<'de, T: DeserializeOwned> Visitor<'de> for EntryAt<'_, T> {
+    type Value = ();
+
+    fn expecting(&self, formatter: &mut Formatter) -> std::fmt::Result {
+        write!(formatter, "a level")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<(), A::Error> {
+        while let Some(key) = map.next_key::<String>()? {
+            match key == self.section {
+                true => map.next_value_seed(EntryAt::<T> { entry: PhantomData, ..self })?,
+                false => { map.next_value::<IgnoredAny>()?; }
+            }
+        }
+        Ok(())
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<(), A::Error> {
+        let mut index = 0;
+        loop {
+            let more = match index == self.index {
+                true => seq.next_element::<T>()?.is_some(),
+                false => seq.next_element::<IgnoredAny>()?.is_some()
+            };
+            if !more { return Ok(()) };
+            index += 1;
+        }
+    }
+}
+
+fn unknown_fields<'a>(section: &str, entries: impl Iterator<Item = &'a Map<String, Value>>) -> Vec<PhysicsError> {
+    entries.enumerate()
+        .flat_map(|(index, unknown)| unknown.keys().map(move |field| entry_error(section, index, PhysicsError::UnknownLevelField(field.clone()))))
+        .collect()
+}
+
+fn entry_error(section: &str, index: usize, error: PhysicsError) -> PhysicsError {
+    PhysicsError::LevelEntry {
+        section: section.to_string(),
+        index,
+        error: Box::new(error)
+    }
+}
+
+/// Everything in a level, checked against the engine and ready to be added to it.
+struct LevelEntities {
+    solids: Vec<Solid>,
+    triggers: Vec<Trigger>,
+    actors: Vec<Actor>
+}
+
+/// The UUIDs a level gives one kind of entity, so that none is used twice and the entries without one get a new one.
+struct LevelUuids {
+    taken: HashSet<Uuid>,
+    /// The entries that still need a UUID.
+    missing: Vec<usize>
+}
+
+impl LevelUuids {
+    fn new() -> Self {
+        Self {
+            taken: HashSet::new(),
+            missing: vec![]
+        }
+    }
+
+    /// Claims the entry's UUID, failing if an earlier entry or something staying in the engine already uses it.
+    fn claim(&mut self, index: usize, uuid: Option<String>, in_engine: impl Fn(Uuid) -> bool, duplicate: fn(Uuid) -> PhysicsError) -> Result<Uuid, PhysicsError> {
+        let Some(uuid) = uuid else {
+            self.missing.push(index);
+            return Ok(Uuid::nil());
+        };
+
+        let uuid = parse_uuid(uuid)?;
+        if in_engine(uuid) || !self.taken.insert(uuid) {
+            return Err(duplicate(uuid));
+        }
+        Ok(uuid)
+    }
+
+    /// A new UUID that nothing in the level or the engine uses.
+    fn fresh(&mut self, in_engine: impl Fn(Uuid) -> bool) -> Uuid {
+        loop {
+            let uuid = Uuid::new_v4();
+            if !in_engine(uuid) && self.taken.insert(uuid) {
+                return uuid;
+            }
+        }
+    }
+}
+
+/// Builds every entity in the level without touching the engine, so that a broken entry leaves the engine as it was.
+fn prepare_level(engine: &PhysicsEngine, level: JsonLevel, options: &LoadOptions) -> Result<LevelEntities, PhysicsError> {
+    let solid_in_engine = |uuid: Uuid| !options.clear_solids && engine.solid_storage.handle_of(uuid).is_some();
+    let trigger_in_engine = |uuid: Uuid| !options.clear_solids && engine.trigger_storage.handle_of(uuid).is_some();
+    let actor_in_engine = |uuid: Uuid| !options.clear_actors && engine.actor_storage.handle_of(uuid).is_some();
+
+    // Solids and tile grids share the solid storage, so they share UUIDs too.
+    let mut solids = vec![];
+    let mut solid_uuids = LevelUuids::new();
+    for (index, collider) in level.level_solids.into_iter().enumerate() {
+        let solid = solid_entry(collider, |uuid| solid_uuids.claim(solids.len(), uuid, solid_in_engine, PhysicsError::DuplicateSolid))
+            .map_err(|error| entry_error("level_solids", index, error))?;
+        solids.push(solid);
+    }
+    for (index, json_grid) in level.level_tile_grids.into_iter().enumerate() {
+        let solid = tile_grid_entry(json_grid, |uuid| solid_uuids.claim(solids.len(), uuid, solid_in_engine, PhysicsError::DuplicateSolid))
+            .map_err(|error| entry_error("level_tile_grids", index, error))?;
+        solids.push(solid);
+    }
+
+    let mut triggers = vec![];
+    let mut trigger_uuids = LevelUuids::new();
+    for (index, collider) in level.level_triggers.into_iter().enumerate() {
+        let uuid = trigger_uuids.claim(index, collider.uuid.clone(), trigger_in_engine, PhysicsError::DuplicateTrigger)
+            .map_err(|error| entry_error("level_triggers", index, error))?;
+        let mut trigger = Trigger::new(collider.collider(), uuid, collider.tags.clone());
+        trigger.layers = collider.layers();
+        triggers.push(trigger);
+    }
+
+    let mut actors = vec![];
+    let mut actor_uuids = LevelUuids::new();
+    for (index, collider) in level.level_actors.into_iter().enumerate() {
+        let uuid = actor_uuids.claim(index, collider.uuid.clone(), actor_in_engine, PhysicsError::DuplicateActor)
+            .map_err(|error| entry_error("level_actors", index, error))?;
+        let mut actor = Actor::new(collider.collider(), uuid);
+        actor.layers = collider.layers();
+        actors.push(actor);
+    }
+
+    // Only now that every UUID in the file is known can new ones be picked that don't clash with them.
+    for index in std::mem::take(&mut solid_uuids.missing) {
+        solids[index].uuid = solid_uuids.fresh(solid_in_engine);
+    }
+    for index in std::mem::take(&mut trigger_uuids.missing) {
+        triggers[index].uuid = trigger_uuids.fresh(trigger_in_engine);
+    }
+    for index in std::mem::take(&mut actor_uuids.missing) {
+        actors[index].uuid = actor_uuids.fresh(actor_in_engine);
+    }
+
+    Ok(LevelEntities { solids, triggers, actors })
+}
+
+fn solid_entry(collider: JsonCollider, claim: impl FnOnce(Option<String>) -> Result<Uuid, PhysicsError>) -> Result<Solid, PhysicsError> {
+    let fixed_uuid = collider.uuid.is_some();
+    let mut solid = Solid::new(collider.collider(), claim(collider.uuid.clone())?, collider.tags.clone());
+    collider.shape(&mut solid);
+    solid.fixed_uuid = fixed_uuid;
+
+    Ok(solid)
+}
+
+fn tile_grid_entry(json_grid: JsonTileGrid, claim: impl FnOnce(Option<String>) -> Result<Uuid, PhysicsError>) -> Result<Solid, PhysicsError> {
+    let grid = json_grid.grid()?;
+    let collider = Collider {
+        enabled: json_grid.enabled,
+        ..grid.bounds(json_grid.x, json_grid.y)
+    };
+
+    let mut solid = Solid::new(collider, claim(json_grid.uuid)?, json_grid.tags);
+    solid.layers = CollisionLayers::new(
+        json_grid.layer.unwrap_or(CollisionLayers::DEFAULT.layer),
+        json_grid.mask.unwrap_or(CollisionLayers::DEFAULT.mask)
+    );
+    solid.tiles = Some(grid);
+
+    Ok(solid)
+}
+
+fn json_level(engine: &PhysicsEngine, save_uuid: bool) -> JsonLevel {
     let mut level = JsonLevel {
-        level_solids: vec![]
+        format_version: LEVEL_FORMAT_VERSION,
+        level_solids: vec![],
+        level_triggers: vec![],
+        level_tile_grids: vec![],
+        level_actors: vec![]
     };
 
-    for solid in engine.solid_storage.solids.iter() {
-        level.level_solids.push( JsonCollider {x: solid.collider.x, y: solid.collider.y, width: solid.collider.width, height: solid.collider.height, tags: None,
-            uuid: match save_uuid { true => {Some(solid.uuid.hyphenated().to_string()) }, false => { None } }}
-        );
+    for solid in engine.solid_storage.iter() {
+        if let Some(grid) = &solid.tiles {
+            level.level_tile_grids.push( JsonTileGrid {x: solid.collider.x, y: solid.collider.y, tile_size: grid.tile_size(), rows: JsonTileGrid::rows(grid), tags: Some(solid.tags.clone()),
+                uuid: match save_uuid { true => {Some(solid.uuid.hyphenated().to_string()) }, false => { None } },
+                enabled: solid.collider.enabled,
+                layer: json_layer(solid.layers.layer, CollisionLayers::DEFAULT.layer), mask: json_layer(solid.layers.mask, CollisionLayers::DEFAULT.mask),
+                unknown: Map::new()}
+            );
+            continue;
+        }
+
+        level.level_solids.push(JsonCollider::from_solid(solid, save_uuid));
+    }
+
+    for trigger in engine.trigger_storage.iter() {
+        level.level_triggers.push(JsonCollider::new(&trigger.collider, Some(trigger.tags.clone()), save_uuid.then_some(trigger.uuid), trigger.layers));
+    }
+
+    for actor in engine.actor_storage.iter() {
+        level.level_actors.push(JsonCollider::new(&actor.collider, None, save_uuid.then_some(actor.uuid), actor.layers));
     }
 
-    let level_json = match serde_json::to_string_pretty(&level) {
-        Ok(level_json) => { level_json }
-        // We SHOULD Never Get Here
-        Err(_) => { return Err("Error, could not read level as json.".to_string()) }
+    level
+}
+
+/// Takes all spawned solids, tile grids, triggers and actors and prints it as a jsonified level.
+/// Like [`save_level_to_writer`] with stdout, but UUIDs are only kept when `save_uuid` is set.
+pub fn save_level(engine: &PhysicsEngine, save_uuid: bool) -> Result<(), PhysicsError> {
+    write_level(engine, std::io::stdout().lock(), "<stdout>", save_uuid)
+}
+
+/// How [`save_level_with`] writes a level.
+#[derive(Debug, Clone, Default)]
+pub struct SaveOptions {
+    /// Leaves out every UUID, so loading the level spawns new ones. Solids that get their UUID from a level are never
+    /// merged, so levels meant to be loaded with [`LoadOptions::merge_solids`] should be saved without them.
+    pub skip_uuids: bool
+}
+
+/// Writes every solid, tile grid, trigger and actor to a level file, with their UUIDs, so that loading it
+/// brings back the same level. Overwrites the file if it exists.
+pub fn save_level_to(engine: &PhysicsEngine, path: &str) -> Result<(), PhysicsError> {
+    let file = match File::create(path) {
+        Ok(file) => { file }
+        Err(error) => { return Err(PhysicsError::LevelIo { path: path.to_string(), message: error.to_string() }) }
     };
 
-    println!("{}", level_json);
+    write_level(engine, BufWriter::new(file), path, true)
+}
 
-    Ok(())
+/// Like [`save_level_to`], but writes the level json to any writer.
+pub fn save_level_to_writer(engine: &PhysicsEngine, writer: impl Write) -> Result<(), PhysicsError> {
+    save_level_with(engine, writer, SaveOptions::default())
+}
+
+/// Writes the level json to any writer, see [`SaveOptions`].
+pub fn save_level_with(engine: &PhysicsEngine, writer: impl Write, options: SaveOptions) -> Result<(), PhysicsError> {
+    write_level(engine, writer, "<writer>", !options.skip_uuids)
+}
+
+fn write_level(engine: &PhysicsEngine, mut writer: impl Write, path: &str, save_uuid: bool) -> Result<(), PhysicsError> {
+    let io_error = |message: String| PhysicsError::LevelIo { path: path.to_string(), message };
 
-}
\ No newline at end of file
+    match serde_json::to_writer_pretty(&mut writer, &json_level(engine, save_uuid)) {
+        Ok(()) => { }
+        Err(error) if error.is_io() => { return Err(io_error(error.to_string())) }
+        Err(error) => { return Err(PhysicsError::LevelSerialize(error.to_string())) }
+    }
+
+    writer.flush().map_err(|error| io_error(error.to_string()))
+}
@@ -3,3 +3,5 @@ pub mod prelude;
 pub mod velocity;
 
 pub mod json_loading;
+
+pub mod platformer;
@@ -0,0 +1,243 @@
+use glam::{vec2, Vec2};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use crate::actor::MoveResult;
+use crate::engine::PhysicsEngine;
+use crate::error::PhysicsError;
+use crate::math::vec2i32;
+
+/// Everything that decides how a [`PlatformerController`] feels. Speeds are in pixels per second, times in seconds.
+/// Fields missing from a loaded config keep their defaults, e.g. `serde_json::from_str::<PlatformerConfig>(r#"{"jump_speed": 300}"#)`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PlatformerConfig {
+    /// Top running speed.
+    pub run_speed: f32,
+    /// How fast running speeds up towards the input.
+    pub run_acceleration: f32,
+    /// How fast running slows down when the input is released or turned around.
+    pub run_deceleration: f32,
+    /// Fraction of the acceleration and deceleration left in the air.
+    pub air_control: f32,
+    /// Multiplies the world gravity, [`PhysicsEngine::gravity`].
+    pub gravity_scale: f32,
+    /// Fastest it can fall.
+    pub max_fall_speed: f32,
+    /// Upwards speed given by a jump.
+    pub jump_speed: f32,
+    /// Multiplies the upwards speed when jump is released early, so short presses make short jumps.
+    pub jump_cut: f32,
+    /// How long after walking off a ledge a jump still works.
+    pub coyote_time: f32,
+    /// How long a jump pressed just before landing is remembered.
+    pub jump_buffer: f32,
+    /// Fastest it can fall while pushing into a wall.
+    pub wall_slide_speed: f32,
+    /// Speed away from the wall given by a wall jump.
+    pub wall_jump_push: f32,
+    /// Upwards speed given by a wall jump.
+    pub wall_jump_speed: f32,
+    /// How long the input can't steer after a wall jump, so it can't climb straight back up the wall.
+    pub wall_jump_lock: f32,
+    /// Furthest it is pulled down to stay on the ground when the ground drops away, e.g. running down slopes.
+    pub ground_snap: i32,
+    /// Adds the speed of the solid it stands on to jumps.
+    pub inherit_platform_velocity: bool
+}
+
+impl Default for PlatformerConfig {
+    fn default() -> Self {
+        Self {
+            run_speed: 150.0,
+            run_acceleration: 1200.0,
+            run_deceleration: 1600.0,
+            air_control: 0.6,
+            gravity_scale: 1.0,
+            max_fall_speed: 400.0,
+            jump_speed: 250.0,
+            jump_cut: 0.5,
+            coyote_time: 0.1,
+            jump_buffer: 0.1,
+            wall_slide_speed: 60.0,
+            wall_jump_push: 150.0,
+            wall_jump_speed: 230.0,
+            wall_jump_lock: 0.15,
+            ground_snap: 4,
+            inherit_platform_velocity: true
+        }
+    }
+}
+
+/// What the player is asking for this tick.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct PlatformerInput {
+    /// From -1 (left) to 1 (right).
+    pub horizontal: f32,
+    /// Whether jump is held. Presses are found by comparing with the previous tick.
+    pub jump: bool
+}
+
+/// Runs, jumps and wall jumps an actor like a platformer character. Y points up, and the ground checks and
+/// [`PlatformerConfig::ground_snap`] always look towards -y, so leave the actor's `down` at (0, -1).
+///
+/// The controller moves the actor itself, so don't attach a [`crate::expanded_engine::velocity::Velocity`] to it too.
+/// Call [`PlatformerController::update`] after solids moved and before [`PhysicsEngine::end_update`],
+/// so that it can see how far the platform under the actor carried it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlatformerController {
+    pub actor_uuid: Uuid,
+    pub config: PlatformerConfig,
+    /// Pixels per second.
+    pub velocity: Vec2,
+    grounded: bool,
+    wall: i32,
+    wall_sliding: bool,
+    coyote: f32,
+    buffered_jump: f32,
+    jumping: bool,
+    jump_held: bool,
+    wall_jump_lock: f32
+}
+
+impl PlatformerController {
+    pub fn new(actor_uuid: Uuid, config: PlatformerConfig) -> Self {
+        Self {
+            actor_uuid,
+            config,
+            velocity: Vec2::ZERO,
+            grounded: false,
+            wall: 0,
+            wall_sliding: false,
+            coyote: 0.0,
+            buffered_jump: 0.0,
+            jumping: false,
+            jump_held: false,
+            wall_jump_lock: 0.0
+        }
+    }
+
+    /// Whether it stood on something at the start of the last update.
+    pub fn is_grounded(&self) -> bool {
+        self.grounded
+    }
+
+    /// The side of the wall it touched at the start of the last update: -1 left, 1 right, 0 none.
+    pub fn wall(&self) -> i32 {
+        self.wall
+    }
+
+    pub fn is_wall_sliding(&self) -> bool {
+        self.wall_sliding
+    }
+
+    /// Applies the input over `dt` seconds and moves the actor.
+    pub fn update(&mut self, engine: &mut PhysicsEngine, input: PlatformerInput, dt: f32) -> Result<MoveResult, PhysicsError> {
+        if dt <= 0.0 { return Ok(MoveResult::default()) };
+        let config = self.config.clone();
+
+        let pushed = engine.actor_storage.get_actor(self.actor_uuid)?.last_push_amount;
+        let platform_velocity = vec2(pushed.x as f32, pushed.y as f32) / dt;
+
+        // Rising through a one way solid touches it without standing on it.
+        self.grounded = self.velocity.y <= 0.0 && engine.check_overlapping_solid(self.actor_uuid, vec2i32(0, -1))?;
+        self.wall = match (engine.check_overlapping_solid(self.actor_uuid, vec2i32(-1, 0))?, engine.check_overlapping_solid(self.actor_uuid, vec2i32(1, 0))?) {
+            (_, true) => 1,
+            (true, false) => -1,
+            (false, false) => 0
+        };
+
+        self.coyote = match self.grounded {
+            true => config.coyote_time,
+            false => (self.coyote - dt).max(0.0)
+        };
+        self.buffered_jump = match input.jump && !self.jump_held {
+            true => config.jump_buffer,
+            false => (self.buffered_jump - dt).max(0.0)
+        };
+        self.jump_held = input.jump;
+        self.wall_jump_lock = (self.wall_jump_lock - dt).max(0.0);
+
+        // Run, speeding up towards the input and slowing down when it is released or turned around.
+        let horizontal = input.horizontal.clamp(-1.0, 1.0);
+        if self.wall_jump_lock == 0.0 {
+            let target = horizontal * config.run_speed;
+            let speeding_up = target != 0.0 && (self.velocity.x == 0.0 || target.signum() == self.velocity.x.signum());
+            let rate = match speeding_up {
+                true => config.run_acceleration,
+                false => config.run_deceleration
+            };
+            let rate = match self.grounded {
+                true => rate,
+                false => rate * config.air_control
+            };
+            self.velocity.x = approach(self.velocity.x, target, rate * dt);
+        }
+
+        // Fall, shortening the jump once it is released.
+        self.velocity += engine.gravity() * config.gravity_scale * dt;
+        if self.jumping && !input.jump && self.velocity.y > 0.0 {
+            self.velocity.y *= config.jump_cut;
+            self.jumping = false;
+        }
+        if self.velocity.y <= 0.0 {
+            self.jumping = false;
+        }
+
+        self.wall_sliding = !self.grounded && self.wall != 0 && horizontal * self.wall as f32 > 0.0 && self.velocity.y < 0.0;
+        let max_fall = match self.wall_sliding {
+            true => config.wall_slide_speed.min(config.max_fall_speed),
+            false => config.max_fall_speed
+        };
+        self.velocity.y = self.velocity.y.max(-max_fall);
+
+        let mut jumped = false;
+        if self.buffered_jump > 0.0 {
+            if self.grounded || self.coyote > 0.0 {
+                self.velocity.y = config.jump_speed;
+                if config.inherit_platform_velocity {
+                    self.velocity.x += platform_velocity.x;
+                    self.velocity.y += platform_velocity.y.max(0.0);
+                }
+                jumped = true;
+            } else if self.wall != 0 {
+                self.velocity = vec2(-self.wall as f32 * config.wall_jump_push, config.wall_jump_speed);
+                self.wall_jump_lock = config.wall_jump_lock;
+                self.wall_sliding = false;
+                jumped = true;
+            }
+        }
+        if jumped {
+            self.buffered_jump = 0.0;
+            self.coyote = 0.0;
+            self.jumping = true;
+        }
+
+        let result = engine.move_actor(self.actor_uuid, self.velocity * dt)?;
+        if result.x.blocked() {
+            self.velocity.x = 0.0;
+        }
+        if result.y.blocked() {
+            self.velocity.y = 0.0;
+            self.jumping = false;
+        }
+
+        // Stay on the ground when it drops away a little, instead of flying off the top of every slope.
+        if self.grounded && !jumped && self.velocity.y <= 0.0 && !result.y.blocked() {
+            let actor = engine.actor_storage.get_actor(self.actor_uuid)?;
+            if let Some(drop) = engine.solid_storage.ground_within(&actor.collider, config.ground_snap, &actor.solid_filter()) {
+                engine.move_actor(self.actor_uuid, vec2(0.0, -drop as f32))?;
+                self.velocity.y = 0.0;
+            }
+        }
+
+        Ok(result)
+    }
+}
+
+/// Moves the value towards the target by at most `step`.
+fn approach(value: f32, target: f32, step: f32) -> f32 {
+    match value < target {
+        true => (value + step).min(target),
+        false => (value - step).max(target)
+    }
+}
@@ -1,5 +1,5 @@
-
-/// Includes all user uses for the expanded engine features.
+//! Includes all user uses for the expanded engine features.
 
 pub use crate::expanded_engine::velocity::*;
-pub use crate::expanded_engine::json_loading::*;
\ No newline at end of file
+pub use crate::expanded_engine::json_loading::*;
+pub use crate::expanded_engine::platformer::*;
\ No newline at end of file
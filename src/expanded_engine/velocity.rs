@@ -1,8 +1,170 @@
-use macroquad::math::Vec2;
-use macroquad::prelude::get_frame_time;
+use std::collections::HashMap;
+use glam::Vec2;
 use uuid::Uuid;
-use crate::actor::CollisionCallback;
-use crate::engine::{PhysicsEngine};
+use crate::actor::{CollisionCallback, MoveResult};
+use crate::engine::{ActorHandle, PhysicsEngine, SolidHandle};
+use crate::error::PhysicsError;
+
+/// How an actor or solid moves on its own, attached to it on the engine and applied by [`PhysicsEngine::integrate`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Velocity {
+    /// Pixels per second.
+    pub velocity: Vec2,
+    /// Multiplies the world gravity, [`VelocityStorage::gravity`]. 0 makes it float. Solids never fall.
+    pub gravity_scale: f32,
+    /// Longest the velocity can get, in pixels per second.
+    pub max_speed: f32,
+    /// Fraction of the velocity lost every second.
+    pub drag: f32,
+    /// Fastest it can fall, in pixels per second along the gravity. Only used by actors.
+    pub terminal_velocity: f32,
+    /// Sets the velocity along an axis to zero whenever a move along it is blocked, so actors stop falling once they land
+    /// and stop running into walls. Only actors get blocked.
+    pub stop_when_blocked: bool,
+    /// What happened the last time [`PhysicsEngine::integrate`] moved the actor. Moves too short to reach the next pixel
+    /// are never blocked, so this alone doesn't tell whether the actor is grounded.
+    pub last_move: MoveResult
+}
+
+impl Default for Velocity {
+    fn default() -> Self {
+        Self {
+            velocity: Vec2::ZERO,
+            gravity_scale: 1.0,
+            max_speed: f32::INFINITY,
+            drag: 0.0,
+            terminal_velocity: f32::INFINITY,
+            stop_when_blocked: true,
+            last_move: MoveResult::default()
+        }
+    }
+}
+
+impl Velocity {
+    pub fn new(velocity: Vec2) -> Self {
+        Self {
+            velocity,
+            ..Default::default()
+        }
+    }
+
+    /// Applies gravity, drag and the speed limits over `dt` seconds, and returns how far to move.
+    fn advance(&mut self, gravity: Vec2, dt: f32) -> Vec2 {
+        self.velocity += gravity * self.gravity_scale * dt;
+        self.velocity *= (1.0 - self.drag * dt).max(0.0);
+        self.velocity = self.velocity.clamp_length_max(self.max_speed);
+
+        let down = gravity.normalize_or_zero();
+        let falling = self.velocity.dot(down);
+        if falling > self.terminal_velocity {
+            self.velocity -= down * (falling - self.terminal_velocity);
+        }
+
+        self.velocity * dt
+    }
+
+    /// Remembers the move and stops along the axes that were blocked.
+    fn blocked(&mut self, result: MoveResult) {
+        if self.stop_when_blocked {
+            if result.x.blocked() {
+                self.velocity.x = 0.0;
+            }
+            if result.y.blocked() {
+                self.velocity.y = 0.0;
+            }
+        }
+
+        self.last_move = result;
+    }
+}
+
+/// The velocities attached to actors and solids, kept on the engine as [`PhysicsEngine::velocities`].
+#[derive(Debug, Clone, Default)]
+pub struct VelocityStorage {
+    /// Acceleration in pixels per second squared applied to every actor velocity, scaled by its gravity scale.
+    /// Zero by default, e.g. (0, -600) makes things fall since y points up.
+    pub gravity: Vec2,
+    actors: HashMap<ActorHandle, Velocity>,
+    solids: HashMap<SolidHandle, Velocity>
+}
+
+impl PhysicsEngine {
+    /// Sets the world gravity, see [`VelocityStorage::gravity`].
+    pub fn set_gravity(&mut self, gravity: Vec2) {
+        self.velocities.gravity = gravity;
+    }
+
+    pub fn gravity(&self) -> Vec2 {
+        self.velocities.gravity
+    }
+
+    /// Attaches the velocity to the actor, replacing the one it had.
+    pub fn set_actor_velocity(&mut self, actor_uuid: Uuid, velocity: Velocity) -> Result<(), PhysicsError> {
+        let handle = self.actor_storage.handle_of(actor_uuid).ok_or_else(|| self.actor_storage.missing_error(actor_uuid))?;
+        self.velocities.actors.insert(handle, velocity);
+        Ok(())
+    }
+
+    /// Returns the actor's velocity, attaching a resting one if it has none.
+    pub fn actor_velocity(&mut self, actor_uuid: Uuid) -> Result<&mut Velocity, PhysicsError> {
+        let handle = self.actor_storage.handle_of(actor_uuid).ok_or_else(|| self.actor_storage.missing_error(actor_uuid))?;
+        Ok(self.velocities.actors.entry(handle).or_default())
+    }
+
+    /// Detaches the actor's velocity, so that [`PhysicsEngine::integrate`] no longer moves it.
+    pub fn remove_actor_velocity(&mut self, actor_uuid: Uuid) -> Option<Velocity> {
+        self.actor_storage.handle_of(actor_uuid).and_then(|handle| self.velocities.actors.remove(&handle))
+    }
+
+    /// Attaches the velocity to the solid, replacing the one it had.
+    pub fn set_solid_velocity(&mut self, solid_uuid: Uuid, velocity: Velocity) -> Result<(), PhysicsError> {
+        let handle = self.solid_storage.handle_of(solid_uuid).ok_or_else(|| self.solid_storage.missing_error(solid_uuid))?;
+        self.velocities.solids.insert(handle, velocity);
+        Ok(())
+    }
+
+    /// Returns the solid's velocity, attaching a resting one if it has none.
+    pub fn solid_velocity(&mut self, solid_uuid: Uuid) -> Result<&mut Velocity, PhysicsError> {
+        let handle = self.solid_storage.handle_of(solid_uuid).ok_or_else(|| self.solid_storage.missing_error(solid_uuid))?;
+        Ok(self.velocities.solids.entry(handle).or_default())
+    }
+
+    /// Detaches the solid's velocity, so that [`PhysicsEngine::integrate`] no longer moves it.
+    pub fn remove_solid_velocity(&mut self, solid_uuid: Uuid) -> Option<Velocity> {
+        self.solid_storage.handle_of(solid_uuid).and_then(|handle| self.velocities.solids.remove(&handle))
+    }
+
+    /// Moves everything with a velocity by it over `dt` seconds. Solids move first, pushing and carrying actors,
+    /// then actors move, stopping along the axes a solid blocked. Both go in storage order, so the result is the same every run.
+    /// Solids squish the actors they push into other solids, like [`PhysicsEngine::move_solid`].
+    pub fn integrate(&mut self, dt: f32) -> Result<(), PhysicsError> {
+        // Velocities of despawned actors and solids can't be reached anymore.
+        self.velocities.actors.retain(|handle, _| self.actor_storage.get(*handle).is_some());
+        self.velocities.solids.retain(|handle, _| self.solid_storage.get(*handle).is_some());
+        let gravity = self.velocities.gravity;
+
+        let solids: Vec<SolidHandle> = self.solid_storage.handles().filter(|handle| self.velocities.solids.contains_key(handle)).collect();
+        for handle in solids {
+            // Gravity only pulls on actors, so moving platforms keep their course.
+            let motion = self.velocities.solids.get_mut(&handle).expect("solid velocity was just found").advance(Vec2::ZERO, dt);
+            if motion != Vec2::ZERO {
+                self.move_solid_by_handle(handle, motion, CollisionCallback::Squish)?;
+            }
+        }
+
+        let actors: Vec<ActorHandle> = self.actor_storage.handles().filter(|handle| self.velocities.actors.contains_key(handle)).collect();
+        for handle in actors {
+            let motion = self.velocities.actors.get_mut(&handle).expect("actor velocity was just found").advance(gravity, dt);
+            let result = match motion != Vec2::ZERO {
+                true => self.move_actor_by_handle(handle, motion, CollisionCallback::None)?,
+                false => MoveResult::default()
+            };
+            self.velocities.actors.get_mut(&handle).expect("actor velocity was just found").blocked(result);
+        }
+
+        Ok(())
+    }
+}
 
 pub struct ActorVelocity {
     pub uuid: Uuid,
@@ -17,10 +179,16 @@ impl ActorVelocity {
         }
     }
 
-    pub fn update(&mut self, engine: &mut PhysicsEngine) -> Result<(), String>{
-        let my_actor = engine.actor_storage.get_actor(self.uuid)?;
+    /// Returns false once the actor has been despawned, at which point this velocity should be dropped.
+    pub fn is_spawned(&self, engine: &PhysicsEngine) -> bool {
+        engine.actor_storage.handle_of(self.uuid).is_some()
+    }
+
+    /// Moves the actor by its velocity over `dt` seconds, e.g. the tick length given by [`PhysicsEngine::step`].
+    pub fn update(&mut self, engine: &mut PhysicsEngine, dt: f32) -> Result<(), PhysicsError>{
+        engine.actor_storage.get_actor(self.uuid)?;
         if self.velocity.x != 0.0 || self.velocity.y != 0.0 {
-            my_actor.move_actor(self.velocity * get_frame_time(), CollisionCallback::None, &engine.solid_storage.solids);
+            engine.move_actor(self.uuid, self.velocity * dt)?;
         }
 
         Ok(())
@@ -40,11 +208,27 @@ impl SolidVelocity {
         }
     }
 
-    pub fn update(&mut self, engine: &mut PhysicsEngine) -> Result<(), String>{
+    /// Returns false once the solid has been despawned, at which point this velocity should be dropped.
+    pub fn is_spawned(&self, engine: &PhysicsEngine) -> bool {
+        engine.solid_storage.handle_of(self.uuid).is_some()
+    }
+
+    /// Moves the solid by its velocity over `dt` seconds, e.g. the tick length given by [`PhysicsEngine::step`].
+    pub fn update(&mut self, engine: &mut PhysicsEngine, dt: f32) -> Result<(), PhysicsError>{
         if self.velocity.x != 0.0 || self.velocity.y != 0.0 {
-            engine.move_solid(self.uuid, self.velocity * get_frame_time())?;
+            engine.move_solid(self.uuid, self.velocity * dt)?;
         }
 
         Ok(())
     }
+}
+
+/// Drops every actor velocity whose actor has been despawned.
+pub fn retain_spawned_actor_velocities(velocities: &mut Vec<ActorVelocity>, engine: &PhysicsEngine) {
+    velocities.retain(|velocity| velocity.is_spawned(engine));
+}
+
+/// Drops every solid velocity whose solid has been despawned.
+pub fn retain_spawned_solid_velocities(velocities: &mut Vec<SolidVelocity>, engine: &PhysicsEngine) {
+    velocities.retain(|velocity| velocity.is_spawned(engine));
 }
\ No newline at end of file
@@ -0,0 +1,49 @@
+use uuid::Uuid;
+use crate::solid::Solid;
+
+/// Bitmask layers deciding which actors, solids and triggers interact.
+/// Two entities collide only when each one's mask contains a layer of the other, so an enemy-only wall
+/// is a solid on its own layer that the player's mask leaves out.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct CollisionLayers {
+    /// The layers this entity is on.
+    pub layer: u32,
+    /// The layers this entity collides with.
+    pub mask: u32
+}
+
+impl CollisionLayers {
+    /// On the first layer and colliding with everything.
+    pub const DEFAULT: CollisionLayers = CollisionLayers { layer: 1, mask: u32::MAX };
+
+    pub fn new(layer: u32, mask: u32) -> Self {
+        Self {
+            layer,
+            mask
+        }
+    }
+
+    pub fn collides_with(&self, other: &CollisionLayers) -> bool {
+        self.mask & other.layer != 0 && other.mask & self.layer != 0
+    }
+}
+
+impl Default for CollisionLayers {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+/// Decides which solids can block a moving collider.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct SolidFilter<'a> {
+    pub layers: CollisionLayers,
+    /// Solids that never block, like the one way platforms an actor is dropping through.
+    pub ignored: &'a [Uuid]
+}
+
+impl SolidFilter<'_> {
+    pub fn allows(&self, solid: &Solid) -> bool {
+        self.layers.collides_with(&solid.layers) && !self.ignored.contains(&solid.uuid)
+    }
+}
@@ -3,6 +3,20 @@ pub mod engine;
 pub mod collider;
 pub mod actor;
 pub mod solid;
+pub mod arena;
+pub mod error;
+pub mod events;
+pub mod layers;
+mod merge;
+mod movement;
+pub mod query;
+pub mod raycast;
+pub mod slope;
+pub mod spatial_hash;
+pub mod storage;
+pub mod tile_grid;
+pub mod timestep;
+pub mod trigger;
 
 pub mod math;
 
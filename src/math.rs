@@ -1,4 +1,4 @@
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
 pub struct Vec2I32 {
     pub x: i32,
     pub y: i32
@@ -9,4 +9,21 @@ pub fn vec2i32(x: i32, y: i32) -> Vec2I32 {
         x,
         y
     }
-}
\ No newline at end of file
+}
+
+/// One of the two axes movement is resolved along.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Axis {
+    X,
+    Y
+}
+
+impl Axis {
+    /// Returns a vector pointing `amount` pixels along this axis.
+    pub fn vec(&self, amount: i32) -> Vec2I32 {
+        match self {
+            Axis::X => vec2i32(amount, 0),
+            Axis::Y => vec2i32(0, amount)
+        }
+    }
+}
@@ -0,0 +1,173 @@
+use std::collections::HashMap;
+use glam::Vec2;
+use uuid::Uuid;
+use crate::collider::Collider;
+use crate::engine::{PhysicsEngine, SolidHandle};
+use crate::solid::Solid;
+
+impl PhysicsEngine {
+    /// Merges touching solids into fewer, larger rectangles covering the same area, to make collision checks cheaper
+    /// and remove the seams between blocks. Only static solids take part: untagged, plain boxes that are enabled,
+    /// have never moved and didn't get their UUID from a level. Solids only merge with solids on the same layers.
+    ///
+    /// Each merged rectangle keeps the UUID of a solid lying entirely inside it, or gets a new one when there is none.
+    /// Returns the UUIDs of the solids that were merged away, which are despawned.
+    pub fn merge_static_solids(&mut self) -> Vec<Uuid> {
+        let members: Vec<SolidHandle> = self.solid_storage.iter_with_handles()
+            .filter(|(_, solid)| is_static(solid))
+            .map(|(handle, _)| handle)
+            .collect();
+        let index_of: HashMap<SolidHandle, usize> = members.iter().enumerate().map(|(index, handle)| (*handle, index)).collect();
+
+        // Group the solids into islands of touching ones.
+        let mut parents: Vec<usize> = (0..members.len()).collect();
+        for (index, handle) in members.iter().enumerate() {
+            let solid = self.solid_storage.get(*handle).expect("static solid was removed");
+            let area = Collider::new(solid.collider.x - 1, solid.collider.y - 1, solid.collider.width + 2, solid.collider.height + 2);
+
+            for other in self.solid_storage.query(&area) {
+                let Some(&other_index) = index_of.get(&other) else { continue };
+                let other_solid = self.solid_storage.get(other).expect("broadphase returned a removed solid");
+                if other_solid.layers != solid.layers || !touching(&solid.collider, &other_solid.collider) { continue };
+
+                let (root, other_root) = (find(&mut parents, index), find(&mut parents, other_index));
+                parents[root.max(other_root)] = root.min(other_root);
+            }
+        }
+
+        let mut islands: Vec<Vec<SolidHandle>> = vec![];
+        let mut island_of_root = HashMap::new();
+        for (index, handle) in members.iter().enumerate() {
+            let root = find(&mut parents, index);
+            let island = *island_of_root.entry(root).or_insert_with(|| {
+                islands.push(vec![]);
+                islands.len() - 1
+            });
+            islands[island].push(*handle);
+        }
+
+        let mut removed = vec![];
+        for island in islands {
+            let colliders: Vec<Collider> = island.iter()
+                .map(|handle| self.solid_storage.get(*handle).expect("static solid was removed").collider.clone())
+                .collect();
+            let rectangles = cover(&colliders);
+            if rectangles.len() >= island.len() { continue };
+
+            // Pick the solids to keep before changing any, since every pick looks at the original colliders.
+            let mut kept: Vec<Option<usize>> = vec![];
+            for rectangle in &rectangles {
+                let inside = (0..island.len()).find(|index| !kept.contains(&Some(*index)) && contains(rectangle, &colliders[*index]));
+                kept.push(inside);
+            }
+
+            let layers = self.solid_storage.get(island[0]).expect("static solid was removed").layers;
+            for (rectangle, index) in rectangles.iter().zip(kept.iter()) {
+                match index {
+                    Some(index) => {
+                        self.solid_storage.get_mut(island[*index]).expect("static solid was removed").collider = rectangle.clone();
+                        self.solid_storage.sync(island[*index]);
+                    }
+                    None => { self.spawn_solid_with_layers(rectangle.clone(), None, layers); }
+                }
+            }
+            for (index, handle) in island.iter().enumerate() {
+                if kept.contains(&Some(index)) { continue };
+
+                let uuid = self.solid_storage.get(*handle).expect("static solid was removed").uuid;
+                self.despawn_solid(uuid).expect("static solid was removed");
+                removed.push(uuid);
+            }
+        }
+
+        removed
+    }
+}
+
+/// Static solids are the only ones that can be merged without changing how the level behaves.
+fn is_static(solid: &Solid) -> bool {
+    solid.tags.is_empty()
+        && solid.one_way.is_none()
+        && solid.slope.is_none()
+        && solid.tiles.is_none()
+        && solid.collider.enabled
+        && !solid.moved
+        && !solid.fixed_uuid
+        && solid.remainder == Vec2::ZERO
+        && solid.collider.width > 0
+        && solid.collider.height > 0
+}
+
+/// Checks if the colliders overlap or share an edge or corner.
+fn touching(a: &Collider, b: &Collider) -> bool {
+    a.min_x() <= b.max_x() && b.min_x() <= a.max_x() && a.min_y() <= b.max_y() && b.min_y() <= a.max_y()
+}
+
+fn contains(outer: &Collider, inner: &Collider) -> bool {
+    outer.min_x() <= inner.min_x() && inner.max_x() <= outer.max_x() && outer.min_y() <= inner.min_y() && inner.max_y() <= outer.max_y()
+}
+
+fn find(parents: &mut [usize], index: usize) -> usize {
+    let mut root = index;
+    while parents[root] != root {
+        root = parents[root];
+    }
+    // Point everything on the way straight at the root so later lookups are quick.
+    let mut index = index;
+    while parents[index] != root {
+        let next = parents[index];
+        parents[index] = root;
+        index = next;
+    }
+    root
+}
+
+/// Covers the same pixels as the colliders with rectangles, by splitting the area along every collider edge into
+/// cells and growing each rectangle as far right, then as far up, as the cells allow.
+/// The cover is greedy rather than minimal, so some shapes end up with more rectangles than they need.
+fn cover(colliders: &[Collider]) -> Vec<Collider> {
+    let mut xs: Vec<i32> = colliders.iter().flat_map(|collider| [collider.min_x(), collider.max_x()]).collect();
+    let mut ys: Vec<i32> = colliders.iter().flat_map(|collider| [collider.min_y(), collider.max_y()]).collect();
+    xs.sort_unstable();
+    xs.dedup();
+    ys.sort_unstable();
+    ys.dedup();
+
+    let (columns, rows) = (xs.len() - 1, ys.len() - 1);
+    let mut filled = vec![false; columns * rows];
+    for collider in colliders {
+        let (first_column, last_column) = (xs.binary_search(&collider.min_x()).unwrap(), xs.binary_search(&collider.max_x()).unwrap());
+        let (first_row, last_row) = (ys.binary_search(&collider.min_y()).unwrap(), ys.binary_search(&collider.max_y()).unwrap());
+        for row in first_row..last_row {
+            for column in first_column..last_column {
+                filled[row * columns + column] = true;
+            }
+        }
+    }
+
+    let mut rectangles = vec![];
+    for row in 0..rows {
+        for column in 0..columns {
+            if !filled[row * columns + column] { continue };
+
+            let mut end_column = column + 1;
+            while end_column < columns && filled[row * columns + end_column] {
+                end_column += 1;
+            }
+            let mut end_row = row + 1;
+            while end_row < rows && (column..end_column).all(|column| filled[end_row * columns + column]) {
+                end_row += 1;
+            }
+
+            // Cells that are covered are cleared, so no two rectangles overlap.
+            for covered_row in row..end_row {
+                for covered_column in column..end_column {
+                    filled[covered_row * columns + covered_column] = false;
+                }
+            }
+            rectangles.push(Collider::new(xs[column], ys[row], xs[end_column] - xs[column], ys[end_row] - ys[row]));
+        }
+    }
+
+    rectangles
+}
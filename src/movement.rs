@@ -0,0 +1,161 @@
+use uuid::Uuid;
+use crate::actor::{AxisMoveResult, CollisionCallback, CollisionInfo, MoveResult};
+use crate::collider::Collider;
+use crate::engine::{ActorHandle, PhysicsEngine};
+use crate::events::PhysicsEvent;
+use crate::layers::{CollisionLayers, SolidFilter};
+use crate::math::{vec2i32, Axis, Vec2I32};
+
+impl PhysicsEngine {
+    /// Moves an actor whole pixels along x then y, colliding with solids and pushing actors that block actors.
+    /// `squisher` is the solid that started the move when it should squish actors, and is only used for events.
+    pub(crate) fn move_actor_exact(&mut self, handle: ActorHandle, distance: Vec2I32, callback: &mut CollisionCallback, squisher: Option<Uuid>) -> MoveResult {
+        // Slopes assume y points up, so only actors falling towards -y stand on them.
+        let slope_step = self.actor_storage.get(handle)
+            .filter(|actor| actor.down == vec2i32(0, -1))
+            .and_then(|actor| self.solid_storage.slope_step_under(&actor.collider, &actor.solid_filter()));
+
+        let x = self.move_actor_along(handle, Axis::X, distance.x, callback, &mut vec![], squisher);
+        // Walking down a slope would leave the actor in the air after every step, so keep it on the ground unless it moves up.
+        if let (Some(step), true) = (slope_step, distance.y <= 0) {
+            let drop = self.actor_storage.get(handle)
+                .and_then(|actor| self.solid_storage.ground_within(&actor.collider, x.travelled.abs() * step, &actor.solid_filter()));
+            if let Some(drop) = drop {
+                self.move_actor_along(handle, Axis::Y, -drop, callback, &mut vec![], squisher);
+            }
+        }
+
+        MoveResult {
+            x,
+            y: self.move_actor_along(handle, Axis::Y, distance.y, callback, &mut vec![], squisher)
+        }
+    }
+
+    /// `chain` holds the actors pushing this one, which can not block it.
+    fn move_actor_along(&mut self, handle: ActorHandle, axis: Axis, distance: i32, callback: &mut CollisionCallback, chain: &mut Vec<ActorHandle>, squisher: Option<Uuid>) -> AxisMoveResult {
+        let mut travelled = 0;
+
+        loop {
+            match self.move_actor_step(handle, axis, distance - travelled, callback, chain, squisher) {
+                Step::Stopped(result) => {
+                    return AxisMoveResult { travelled: travelled + result.travelled, hit: result.hit };
+                }
+                Step::Climbing { travelled: step_travelled, height, info } => {
+                    travelled += step_travelled;
+                    let rise = self.move_actor_along(handle, Axis::Y, height, callback, chain, squisher);
+                    if rise.travelled != height {
+                        let actor = self.actor_storage.get_mut(handle).expect("climbing actor was removed");
+                        actor.stop(axis, 0, callback, &info);
+                        return AxisMoveResult { travelled, hit: Some(info) };
+                    }
+                }
+            }
+        }
+    }
+
+    /// Moves the actor until it stops or reaches a slope it can climb.
+    fn move_actor_step(&mut self, handle: ActorHandle, axis: Axis, distance: i32, callback: &mut CollisionCallback, chain: &mut Vec<ActorHandle>, squisher: Option<Uuid>) -> Step {
+        if distance == 0 { return Step::Stopped(AxisMoveResult::default()) };
+        let (collider, layers, dropping_through) = match self.actor_storage.get(handle) {
+            Some(actor) => (actor.collider.clone(), actor.layers, actor.dropping_through.clone()),
+            None => return Step::Stopped(AxisMoveResult::default())
+        };
+        let sign = distance.signum();
+
+        // Solids decide how far the actor can get at all.
+        let filter = SolidFilter { layers, ignored: &dropping_through };
+        let (mut limit, mut hit, mut climb) = match self.solid_storage.blocking_hit(&collider, axis, distance, &filter) {
+            Some((free, info, climb)) => (free, Some(info), climb),
+            None => (distance.abs(), None, None)
+        };
+
+        // Blocking actors in the way are pushed as far as this actor wants to go, closest first.
+        // The mover's callback is only for the mover, pushed actors get squished if a solid started the push.
+        let mut push_callback = match squisher {
+            Some(_) => CollisionCallback::Squish,
+            None => CollisionCallback::None
+        };
+        chain.push(handle);
+        for blocker in self.blocking_actors_in_path(&collider, layers, axis, distance, chain) {
+            if limit == 0 { break };
+
+            let blocker_collider = self.actor_storage.get(blocker).expect("blocking actor was just found").collider.clone();
+            // Pushing an earlier blocker may already have pushed this one out of the way.
+            let free = match collider.sweep(axis, limit * sign, &blocker_collider) {
+                Some(free) => free,
+                None => continue
+            };
+
+            let pushed = self.move_actor_along(blocker, axis, (limit - free) * sign, &mut push_callback, chain, squisher);
+            let blocker_uuid = self.actor_storage.get(blocker).expect("pushed actor was removed").uuid;
+            if pushed.travelled != 0 {
+                let pusher_uuid = self.actor_storage.get(handle).expect("pushing actor was removed").uuid;
+                self.events.push(PhysicsEvent::ActorPushedByActor { actor_uuid: blocker_uuid, pusher_uuid, motion: axis.vec(pushed.travelled) });
+            }
+            if let Some(collision) = pushed.hit {
+                self.record_blocked(blocker_uuid, collision, squisher);
+            }
+
+            let reached = free + pushed.travelled.abs();
+            if reached < limit {
+                limit = reached;
+                hit = Some(CollisionInfo::actor(blocker_uuid, axis.vec(-sign)));
+                climb = None;
+            }
+        }
+        chain.pop();
+
+        let actor = self.actor_storage.get_mut(handle).expect("moving actor was removed");
+        let step = match (hit, climb) {
+            (Some(info), Some(height)) => {
+                actor.collider.translate(axis, limit * sign);
+                Step::Climbing { travelled: limit * sign, height, info }
+            }
+            (Some(info), None) => {
+                actor.stop(axis, limit * sign, callback, &info);
+                Step::Stopped(AxisMoveResult { travelled: limit * sign, hit: Some(info) })
+            }
+            (None, _) => {
+                actor.collider.translate(axis, distance);
+                Step::Stopped(AxisMoveResult { travelled: distance, hit: None })
+            }
+        };
+        self.actor_storage.sync(handle);
+
+        step
+    }
+
+    /// Actors that block actors and would be touched by the collider moving along the axis, closest first.
+    fn blocking_actors_in_path(&self, collider: &Collider, layers: CollisionLayers, axis: Axis, distance: i32, chain: &[ActorHandle]) -> Vec<ActorHandle> {
+        let mut blockers = vec![];
+
+        for handle in self.actor_storage.query(&collider.swept(axis.vec(distance))) {
+            if chain.contains(&handle) { continue };
+
+            let actor = self.actor_storage.get(handle).expect("broadphase returned a removed actor");
+            if !actor.blocks_actors || !layers.collides_with(&actor.layers) { continue };
+
+            if let Some(free) = collider.sweep(axis, distance, &actor.collider) {
+                blockers.push((free, handle));
+            }
+        }
+
+        // The query returns handles in storage order, and the sort is stable, so ties stay deterministic.
+        blockers.sort_by_key(|(free, _)| *free);
+        blockers.into_iter().map(|(_, handle)| handle).collect()
+    }
+
+    pub(crate) fn record_blocked(&mut self, actor_uuid: Uuid, collision: CollisionInfo, squisher: Option<Uuid>) {
+        self.events.push(match squisher {
+            Some(solid_uuid) => PhysicsEvent::ActorSquished { actor_uuid, solid_uuid, collision },
+            None => PhysicsEvent::ActorBlocked { actor_uuid, collision }
+        });
+    }
+}
+
+/// How far one part of a move got.
+enum Step {
+    Stopped(AxisMoveResult),
+    /// Reached a slope that the actor can keep walking up after rising by the height.
+    Climbing { travelled: i32, height: i32, info: CollisionInfo }
+}
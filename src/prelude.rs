@@ -4,5 +4,17 @@ pub use crate::solid::*;
 pub use crate::actor::*;
 pub use crate::collider::*;
 pub use crate::engine::*;
+pub use crate::arena::*;
+pub use crate::error::*;
+pub use crate::events::*;
+pub use crate::query::*;
+pub use crate::raycast::*;
+pub use crate::layers::*;
+pub use crate::slope::*;
+pub use crate::spatial_hash::*;
+pub use crate::storage::*;
+pub use crate::tile_grid::*;
+pub use crate::timestep::*;
+pub use crate::trigger::*;
 
 pub use crate::math::*;
\ No newline at end of file
@@ -0,0 +1,260 @@
+use glam::{vec2, Vec2};
+use uuid::Uuid;
+use crate::actor::Actor;
+use crate::collider::Collider;
+use crate::engine::PhysicsEngine;
+use crate::math::{vec2i32, Vec2I32};
+use crate::slope::Slope;
+use crate::solid::Solid;
+
+/// Decides which entities raycasts, shape casts and area queries can find.
+#[derive(Debug, Clone)]
+pub struct QueryFilter {
+    /// Only entities on one of these layers are found.
+    pub mask: u32,
+    /// When set, only solids with this tag are found. Actors have no tags, so this skips them too.
+    pub tag: Option<String>,
+    /// Solids with this tag are never found.
+    pub exclude_tag: Option<String>,
+    /// Solids and actors that are never found, e.g. the one doing the query.
+    pub ignored: Vec<Uuid>
+}
+
+impl Default for QueryFilter {
+    fn default() -> Self {
+        Self {
+            mask: u32::MAX,
+            tag: None,
+            exclude_tag: None,
+            ignored: vec![]
+        }
+    }
+}
+
+impl QueryFilter {
+    pub fn allows(&self, solid: &Solid) -> bool {
+        solid.layers.layer & self.mask != 0
+            && self.tag.as_ref().map_or(true, |tag| solid.has_tag(tag))
+            && self.exclude_tag.as_ref().map_or(true, |tag| !solid.has_tag(tag))
+            && !self.ignored.contains(&solid.uuid)
+    }
+
+    pub fn allows_actor(&self, actor: &Actor) -> bool {
+        actor.layers.layer & self.mask != 0
+            && self.tag.is_none()
+            && !self.ignored.contains(&actor.uuid)
+    }
+}
+
+/// Everything an area query found, each in storage order.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct QueryResult {
+    pub solids: Vec<Uuid>,
+    pub actors: Vec<Uuid>
+}
+
+impl QueryResult {
+    pub fn is_empty(&self) -> bool {
+        self.solids.is_empty() && self.actors.is_empty()
+    }
+}
+
+/// Where a shape cast was stopped.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ShapeCastHit {
+    pub solid_uuid: Uuid,
+    /// How far the collider got before touching the solid, as a fraction of the cast from 0 to 1.
+    pub time: f32,
+    /// The furthest offset along the cast where the collider is still free.
+    pub offset: Vec2I32,
+    /// Points out of the side of the solid that stopped the cast, along the axis that collided.
+    pub normal: Vec2I32
+}
+
+impl PhysicsEngine {
+    /// Returns the solids and actors overlapping the area. Slopes count only below their surface.
+    pub fn query_rect(&self, area: &Collider, filter: &QueryFilter) -> QueryResult {
+        QueryResult {
+            solids: self.solid_storage.query(area).into_iter()
+                .filter_map(|handle| self.solid_storage.get(handle))
+                .filter(|solid| filter.allows(solid) && solid.is_overlapping(vec2i32(0, 0), area))
+                .map(|solid| solid.uuid)
+                .collect(),
+            actors: self.actor_storage.query(area).into_iter()
+                .filter_map(|handle| self.actor_storage.get(handle))
+                .filter(|actor| filter.allows_actor(actor) && actor.collider.is_overlapping(vec2i32(0, 0), area))
+                .map(|actor| actor.uuid)
+                .collect()
+        }
+    }
+
+    /// Returns the solids and actors covering the pixel.
+    pub fn query_point(&self, point: Vec2I32, filter: &QueryFilter) -> QueryResult {
+        self.query_rect(&Collider::new(point.x, point.y, 1, 1), filter)
+    }
+
+    /// Returns the solids and actors overlapping the circle.
+    pub fn query_circle(&self, center: Vec2, radius: f32, filter: &QueryFilter) -> QueryResult {
+        if radius.is_nan() || radius < 0.0 { return QueryResult::default() };
+        let bounds = Collider::covering(center - radius, center + radius, 0);
+
+        QueryResult {
+            solids: self.solid_storage.query(&bounds).into_iter()
+                .filter_map(|handle| self.solid_storage.get(handle))
+                .filter(|solid| filter.allows(solid) && solid.collider.enabled && circle_overlaps_solid(center, radius, solid, &bounds))
+                .map(|solid| solid.uuid)
+                .collect(),
+            actors: self.actor_storage.query(&bounds).into_iter()
+                .filter_map(|handle| self.actor_storage.get(handle))
+                .filter(|actor| filter.allows_actor(actor) && actor.collider.enabled && circle_overlaps(center, radius, &outline(&actor.collider, None)))
+                .map(|actor| actor.uuid)
+                .collect()
+        }
+    }
+
+    /// Moves a copy of the collider along the cast, a pixel along each axis at a time, and returns the first solid it would run into.
+    /// Nothing is moved, so this can check whether an area is clear before spawning something there.
+    pub fn shape_cast(&self, collider: &Collider, cast: Vec2I32, filter: &QueryFilter) -> Option<ShapeCastHit> {
+        let steps = cast.x.abs().max(cast.y.abs());
+        if steps == 0 || !collider.enabled { return None };
+        let line = CastLine { cast, steps };
+
+        let mut closest: Option<(i32, &Solid)> = None;
+        for solid in self.solid_storage.query(&collider.swept(cast)).into_iter().filter_map(|handle| self.solid_storage.get(handle)) {
+            if !filter.allows(solid) || !solid.collider.enabled { continue };
+            let last = closest.map_or(steps, |(step, _)| step - 1);
+            if let Some(step) = line.first_blocked_step(collider, solid, last) {
+                closest = Some((step, solid));
+            }
+        }
+
+        let (step, solid) = closest?;
+        let (free, next) = (line.at(step - 1), line.at(step));
+        // Moving along x alone already overlapping means the side was hit, otherwise the top or bottom was.
+        let motion = vec2i32(next.x - free.x, next.y - free.y);
+        let normal = match motion.x != 0 && solid.is_overlapping(vec2i32(next.x, free.y), collider) {
+            true => vec2i32(-motion.x.signum(), 0),
+            false => vec2i32(0, -motion.y.signum())
+        };
+
+        Some(ShapeCastHit { solid_uuid: solid.uuid, time: (step - 1) as f32 / steps as f32, offset: free, normal })
+    }
+}
+
+/// The offsets a shape cast passes through: step `k` of `steps` is the cast scaled by `k / steps`, rounded to the nearest pixel,
+/// so every step moves at most one pixel along each axis.
+struct CastLine {
+    cast: Vec2I32,
+    steps: i32
+}
+
+impl CastLine {
+    fn at(&self, step: i32) -> Vec2I32 {
+        vec2i32(self.along(self.cast.x, step), self.along(self.cast.y, step))
+    }
+
+    /// Rounds `distance * step / steps` half away from zero, without going through floats.
+    fn along(&self, distance: i32, step: i32) -> i32 {
+        let scaled = distance as i64 * step as i64;
+        let steps = self.steps as i64;
+        ((2 * scaled.abs() + steps) / (2 * steps) * scaled.signum()) as i32
+    }
+
+    /// The first step up to `last` where the collider overlaps the solid and the solid blocks the step.
+    /// Both coordinates only ever move one way, so the steps inside the solid's bounds along each axis form a range
+    /// found by bisection, like the sweeps actors move with. A plain box blocks at the first step inside both ranges,
+    /// while slopes, one way solids and tile grids are checked step by step, only inside those ranges.
+    fn first_blocked_step(&self, collider: &Collider, solid: &Solid, last: i32) -> Option<i32> {
+        let bounds = &solid.collider;
+        let (x_first, x_last) = self.steps_between(self.cast.x, bounds.min_x() - collider.max_x() + 1, bounds.max_x() - collider.min_x() - 1)?;
+        let (y_first, y_last) = self.steps_between(self.cast.y, bounds.min_y() - collider.max_y() + 1, bounds.max_y() - collider.min_y() - 1)?;
+        let (first, last) = (x_first.max(y_first), x_last.min(y_last).min(last));
+        if first > last { return None };
+
+        if solid.slope.is_none() && solid.one_way.is_none() && solid.tiles.is_none() {
+            return Some(first);
+        }
+
+        (first..=last).find(|step| {
+            let (free, next) = (self.at(step - 1), self.at(*step));
+            solid.is_overlapping(next, collider) && solid.blocks(&collider.moved(free), vec2i32(next.x - free.x, next.y - free.y))
+        })
+    }
+
+    /// The range of steps, from 1 to `steps`, where the offset along the axis lies between `min` and `max`.
+    fn steps_between(&self, distance: i32, min: i32, max: i32) -> Option<(i32, i32)> {
+        let (first, last) = match distance >= 0 {
+            // The offset only grows, so the steps below `min` come first and the ones past `max` last.
+            true => (self.first_step(|step| self.along(distance, step) >= min), self.first_step(|step| self.along(distance, step) > max) - 1),
+            false => (self.first_step(|step| self.along(distance, step) <= max), self.first_step(|step| self.along(distance, step) < min) - 1)
+        };
+
+        (first <= last).then_some((first, last))
+    }
+
+    /// The first step from 1 to `steps` where the condition holds, or `steps + 1` if it never does.
+    /// The condition has to stay true once it is.
+    fn first_step(&self, condition: impl Fn(i32) -> bool) -> i32 {
+        let (mut low, mut high) = (1, self.steps + 1);
+        while low < high {
+            let middle = low + (high - low) / 2;
+            match condition(middle) {
+                true => high = middle,
+                false => low = middle + 1
+            }
+        }
+        low
+    }
+}
+
+/// Checks the circle against the solid's shape, or against each tile under its bounds for tile grids.
+fn circle_overlaps_solid(center: Vec2, radius: f32, solid: &Solid, bounds: &Collider) -> bool {
+    match solid.tiles {
+        Some(_) => {
+            // One pixel of margin so that tiles the circle just touches are still checked.
+            let area = Collider::new(bounds.x - 1, bounds.y - 1, bounds.width + 2, bounds.height + 2);
+            solid.tile_solids(&area).any(|tile| circle_overlaps(center, radius, &outline(&tile.collider, tile.slope)))
+        }
+        None => circle_overlaps(center, radius, &outline(&solid.collider, solid.slope))
+    }
+}
+
+/// Corners of the collider, or of the slope filling it, going around the shape.
+fn outline(collider: &Collider, slope: Option<Slope>) -> Vec<Vec2> {
+    let (min_x, min_y, max_x, max_y) = (collider.min_x() as f32, collider.min_y() as f32, collider.max_x() as f32, collider.max_y() as f32);
+
+    match slope {
+        Some(Slope::UpRight) => vec![vec2(min_x, min_y), vec2(max_x, min_y), vec2(max_x, max_y)],
+        Some(Slope::UpLeft) => vec![vec2(min_x, min_y), vec2(max_x, min_y), vec2(min_x, max_y)],
+        None => vec![vec2(min_x, min_y), vec2(max_x, min_y), vec2(max_x, max_y), vec2(min_x, max_y)]
+    }
+}
+
+/// Checks if the circle overlaps the convex shape: either the center is inside it, or an edge is closer than the radius.
+fn circle_overlaps(center: Vec2, radius: f32, corners: &[Vec2]) -> bool {
+    let mut inside = true;
+    let mut area = 0.0;
+
+    for (index, start) in corners.iter().enumerate() {
+        let end = corners[(index + 1) % corners.len()];
+        let edge = end - *start;
+        area += start.perp_dot(end);
+
+        // The corners go counter clockwise, so the center is inside when it is left of every edge.
+        if edge.perp_dot(center - *start) < 0.0 {
+            inside = false;
+        }
+
+        let along = if edge.length_squared() > 0.0 {
+            ((center - *start).dot(edge) / edge.length_squared()).clamp(0.0, 1.0)
+        } else {
+            0.0
+        };
+        if center.distance_squared(*start + edge * along) < radius * radius {
+            return true;
+        }
+    }
+
+    // Colliders without any area have no inside.
+    inside && area > 0.0
+}
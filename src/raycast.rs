@@ -0,0 +1,133 @@
+use glam::{vec2, Vec2};
+use uuid::Uuid;
+use crate::collider::Collider;
+use crate::engine::PhysicsEngine;
+use crate::query::QueryFilter;
+use crate::slope::Slope;
+use crate::solid::Solid;
+
+/// Where a ray hit a solid.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RayHit {
+    pub solid_uuid: Uuid,
+    pub point: Vec2,
+    /// Distance from the origin to the point.
+    pub distance: f32,
+    /// Unit normal of the face that was hit. Zero when the ray starts inside the solid.
+    pub normal: Vec2
+}
+
+impl PhysicsEngine {
+    /// Returns the first solid hit by a ray from the origin along the direction, up to the max distance.
+    /// One way solids are only hit through their face, while disabled solids and actors never are.
+    pub fn raycast(&self, origin: Vec2, direction: Vec2, max_distance: f32, filter: &QueryFilter) -> Option<RayHit> {
+        self.raycast_all(origin, direction, max_distance, filter).into_iter().next()
+    }
+
+    /// Returns every solid hit by the ray, closest first. Ties go to the solid stored first.
+    pub fn raycast_all(&self, origin: Vec2, direction: Vec2, max_distance: f32, filter: &QueryFilter) -> Vec<RayHit> {
+        let direction = direction.normalize_or_zero();
+        if direction == Vec2::ZERO || max_distance.is_nan() || max_distance < 0.0 { return vec![] };
+
+        let area = match max_distance.is_finite() {
+            true => {
+                let end = origin + direction * max_distance;
+                // One pixel of margin so that rays running along an edge still find the solid.
+                Some(Collider::covering(origin.min(end), origin.max(end), 1))
+            }
+            false => None
+        };
+        let candidates = match &area {
+            Some(area) => self.solid_storage.query(area),
+            None => self.solid_storage.handles().collect()
+        };
+
+        let mut hits = vec![];
+        for handle in candidates {
+            let solid = self.solid_storage.get(handle).expect("broadphase returned a removed solid");
+            if !solid.collider.enabled || !filter.allows(solid) { continue };
+
+            if let Some((distance, normal)) = ray_solid(origin, direction, solid, area.as_ref()) {
+                if distance <= max_distance {
+                    hits.push(RayHit { solid_uuid: solid.uuid, point: origin + direction * distance, distance, normal });
+                }
+            }
+        }
+
+        // The candidates are in storage order and the sort is stable, so ties stay deterministic.
+        hits.sort_by(|a, b| a.distance.total_cmp(&b.distance));
+        hits
+    }
+}
+
+/// Distance along the ray to where it enters the solid, and the normal of the face it enters through.
+/// The solid is clipped as a convex shape: its box, cut along the surface for slopes.
+/// Tile grids are clipped tile by tile, only looking at the tiles inside the area the ray covers.
+fn ray_solid(origin: Vec2, direction: Vec2, solid: &Solid, area: Option<&Collider>) -> Option<(f32, Vec2)> {
+    if solid.tiles.is_some() {
+        let bounds = &solid.collider;
+        let area = match area {
+            Some(area) => {
+                let (min_x, min_y) = (area.min_x().max(bounds.min_x()), area.min_y().max(bounds.min_y()));
+                Collider::new(min_x, min_y, area.max_x().min(bounds.max_x()) - min_x, area.max_y().min(bounds.max_y()) - min_y)
+            }
+            None => bounds.clone()
+        };
+        if area.width <= 0 || area.height <= 0 { return None };
+
+        return solid.tile_solids(&area)
+            .filter_map(|tile| ray_solid(origin, direction, &tile, None))
+            .min_by(|a, b| a.0.total_cmp(&b.0));
+    }
+
+    let collider = &solid.collider;
+    let (min, max) = (vec2(collider.min_x() as f32, collider.min_y() as f32), vec2(collider.max_x() as f32, collider.max_y() as f32));
+
+    // Every face as an outward normal and a point on it.
+    let mut faces = vec![
+        (vec2(-1.0, 0.0), min),
+        (vec2(0.0, -1.0), min),
+        (vec2(1.0, 0.0), max),
+        (vec2(0.0, 1.0), max)
+    ];
+    match solid.slope {
+        Some(Slope::UpRight) => faces.push((vec2(-(collider.height as f32), collider.width as f32).normalize_or_zero(), min)),
+        Some(Slope::UpLeft) => faces.push((vec2(collider.height as f32, collider.width as f32).normalize_or_zero(), vec2(max.x, min.y))),
+        None => { }
+    }
+
+    let mut enter = f32::NEG_INFINITY;
+    let mut exit = f32::INFINITY;
+    let mut normal = Vec2::ZERO;
+    for (face_normal, point) in faces {
+        let towards = face_normal.dot(direction);
+        let outside = face_normal.dot(origin - point);
+
+        if towards == 0.0 {
+            if outside > 0.0 { return None };
+        } else if towards < 0.0 {
+            let t = -outside / towards;
+            if t > enter {
+                enter = t;
+                normal = face_normal;
+            }
+        } else {
+            exit = exit.min(-outside / towards);
+        }
+    }
+
+    if enter > exit || exit < 0.0 { return None };
+
+    if enter < 0.0 {
+        // Starting inside a one way solid never hits it, starting inside anything else hits straight away.
+        return match solid.one_way {
+            Some(_) => None,
+            None => Some((0.0, Vec2::ZERO))
+        };
+    }
+
+    match solid.one_way {
+        Some(face) if vec2(face.x as f32, face.y as f32) != normal => None,
+        _ => Some((enter, normal))
+    }
+}
@@ -1,15 +1,45 @@
-use macroquad::math::Vec2;
+use glam::Vec2;
 use uuid::Uuid;
-use crate::actor::{Actor};
-use crate::math::{vec2i32, Vec2I32};
+use crate::actor::Actor;
+use crate::engine::{ActorHandle, ActorStorage};
+use crate::layers::CollisionLayers;
+use crate::math::{vec2i32, Axis, Vec2I32};
 use crate::prelude::Collider;
+use crate::slope::Slope;
+use crate::tile_grid::{Tile, TileGrid};
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug)]
 pub struct Solid {
     pub uuid: Uuid,
     pub remainder: Vec2,
     pub collider: Collider,
-    pub(crate) tags: Vec<String>
+    pub(crate) tags: Vec<String>,
+    /// Makes this a one way solid, like a jump-through ledge, that only blocks movement into one of its faces.
+    /// This is the normal of that face, e.g. (0, 1) for a platform that can be jumped through from below.
+    pub one_way: Option<Vec2I32>,
+    /// Makes this a slope that actors walk up and down instead of being blocked by.
+    pub slope: Option<Slope>,
+    pub layers: CollisionLayers,
+    /// Makes this a grid of tiles covering the collider, which then ignores `one_way` and `slope` in favour of each tile's kind.
+    pub tiles: Option<TileGrid>,
+    /// Set once the solid has been moved, so level optimisation leaves it alone.
+    pub(crate) moved: bool,
+    /// Set when a level gave the solid its UUID, which something else may refer to, so level optimisation leaves it alone.
+    pub(crate) fixed_uuid: bool
+}
+
+/// Solids are equal when they have the same UUID and collide the same way. What level optimisation keeps track of isn't compared.
+impl PartialEq for Solid {
+    fn eq(&self, other: &Self) -> bool {
+        self.uuid == other.uuid
+            && self.remainder == other.remainder
+            && self.collider == other.collider
+            && self.tags == other.tags
+            && self.one_way == other.one_way
+            && self.slope == other.slope
+            && self.layers == other.layers
+            && self.tiles == other.tiles
+    }
 }
 
 impl Solid {
@@ -20,11 +50,208 @@ impl Solid {
             uuid,
             remainder: Default::default(),
             collider,
-            tags: match tags {
-                Some(tags) => tags,
-                None => vec![]
+            tags: tags.unwrap_or_default(),
+            one_way: None,
+            slope: None,
+            layers: CollisionLayers::DEFAULT,
+            tiles: None,
+            moved: false,
+            fixed_uuid: false
+        }
+    }
+
+    /// The tiles of a tile grid that share a pixel with the area, each as a solid of its own with this solid's uuid and layers.
+    pub(crate) fn tile_solids<'a>(&'a self, area: &Collider) -> impl Iterator<Item = Solid> + 'a {
+        let area = area.clone();
+        self.tiles.iter().flat_map(move |grid| grid.tiles_in(&self.collider, &area).map(move |(column, row, tile)| Solid {
+            uuid: self.uuid,
+            remainder: Vec2::ZERO,
+            collider: grid.tile_collider(&self.collider, column, row),
+            tags: vec![],
+            one_way: (tile == Tile::OneWay).then_some(vec2i32(0, 1)),
+            slope: match tile {
+                Tile::Slope(slope) => Some(slope),
+                _ => None
+            },
+            layers: self.layers,
+            tiles: None,
+            moved: self.moved,
+            fixed_uuid: self.fixed_uuid
+        }))
+    }
+
+    /// Checks if the collider moved by the offset overlaps the solid, following the surface of slopes.
+    pub fn is_overlapping(&self, offset: Vec2I32, collider: &Collider) -> bool {
+        if self.tiles.is_some() {
+            return self.tile_solids(&collider.moved(offset)).any(|tile| tile.is_overlapping(offset, collider));
+        }
+
+        match self.slope {
+            Some(slope) => slope.is_overlapping(&self.collider, offset, collider),
+            None => collider.is_overlapping(offset, &self.collider)
+        }
+    }
+
+    /// Returns how many pixels the collider can move along the axis before it would overlap the solid,
+    /// or None if the full distance is free. Solids that don't block the move, like one way solids passed from behind, never stop it.
+    pub fn sweep(&self, collider: &Collider, axis: Axis, distance: i32) -> Option<i32> {
+        if self.tiles.is_some() {
+            return self.tile_solids(&collider.swept(axis.vec(distance)))
+                .filter_map(|tile| tile.sweep(collider, axis, distance))
+                .min();
+        }
+        if !self.blocks(collider, axis.vec(distance)) { return None };
+
+        match self.slope {
+            Some(slope) => slope.sweep(&self.collider, collider, axis, distance),
+            None => collider.sweep(axis, distance, &self.collider)
+        }
+    }
+
+    /// How far the collider has to rise to stand on this slope after stepping one pixel along x,
+    /// or None if that takes more than one step of the slope.
+    pub fn climb_height(&self, collider: &Collider, step: i32) -> Option<i32> {
+        if self.tiles.is_some() {
+            // Every tile in the way has to be a slope that can be climbed.
+            let mut height = None;
+            for tile in self.tile_solids(&collider.moved(vec2i32(step, 0))) {
+                if !tile.is_overlapping(vec2i32(step, 0), collider) || !tile.blocks(collider, vec2i32(step, 0)) { continue };
+                let needed = tile.climb_height(collider, step)?;
+                height = Some(height.map_or(needed, |height: i32| height.max(needed)));
+            }
+            return height;
+        }
+
+        let slope = self.slope?;
+        let stepped = Collider { x: collider.x + step, ..collider.clone() };
+        let height = slope.surface_under(&self.collider, &stepped)? - collider.min_y();
+
+        (height > 0 && height <= slope.step_height(&self.collider)).then_some(height)
+    }
+
+    /// The step height of the slope right below the collider, if this is a slope or a tile grid with one there.
+    pub fn slope_step_under(&self, collider: &Collider) -> Option<i32> {
+        let below = vec2i32(0, -1);
+        if self.tiles.is_some() {
+            return self.tile_solids(&collider.moved(below)).filter_map(|tile| tile.slope_step_under(collider)).max();
+        }
+
+        match self.slope {
+            Some(slope) if self.is_overlapping(below, collider) => Some(slope.step_height(&self.collider)),
+            _ => None
+        }
+    }
+
+    /// Motion that moves an actor overlapping this solid out of it, in the direction of the original motion.
+    /// The sloped face of a slope pushes actors up onto the surface instead.
+    pub(crate) fn push_out_motion(&self, actor: &Collider, original: Vec2I32) -> Vec2I32 {
+        if self.tiles.is_some() {
+            // The tile that pushes furthest also clears the ones behind it.
+            return self.tile_solids(actor)
+                .filter(|tile| tile.is_overlapping(vec2i32(0, 0), actor))
+                .map(|tile| tile.push_out_motion(actor, original))
+                .max_by_key(|motion| motion.x.abs() + motion.y.abs())
+                .unwrap_or_default();
+        }
+
+        let sloped_face_leads = match self.slope {
+            Some(Slope::UpRight) => original.y > 0 || original.x < 0,
+            Some(Slope::UpLeft) => original.y > 0 || original.x > 0,
+            None => false
+        };
+        if let (true, Some(slope)) = (sloped_face_leads, self.slope) {
+            if let Some(surface) = slope.surface_under(&self.collider, actor) {
+                return vec2i32(0, surface - actor.min_y());
             }
         }
+
+        let pusher = &self.collider;
+        if original.x > 0 {
+            vec2i32(pusher.max_x() - actor.min_x(), 0)
+        } else if original.x < 0 {
+            vec2i32(pusher.min_x() - actor.max_x(), 0)
+        } else if original.y > 0 {
+            vec2i32(0, pusher.max_y() - actor.min_y())
+        } else {
+            vec2i32(0, pusher.min_y() - actor.max_y())
+        }
+    }
+
+    /// Motion that carries a riding actor along with this solid. Riders of slopes also follow the surface.
+    fn carry_motion(&self, actor: &Collider, motion: Vec2I32) -> Vec2I32 {
+        let moved = actor.moved(motion);
+        if self.tiles.is_some() {
+            return self.tile_solids(&moved).map(|tile| tile.carry_motion(actor, motion)).max_by_key(|motion| motion.y).unwrap_or(motion);
+        }
+
+        match self.slope.and_then(|slope| slope.surface_under(&self.collider, &moved)) {
+            Some(surface) => vec2i32(motion.x, motion.y + (surface - moved.min_y()).max(0)),
+            None => motion
+        }
+    }
+
+    /// Works out how moving from `previous` by the motion affects the actor, if at all.
+    fn interaction_with(&self, previous: &Collider, handle: ActorHandle, actor: &Actor, actors: &ActorStorage, motion: Vec2I32) -> Option<SolidInteraction> {
+        if !self.layers.collides_with(&actor.layers) { return None };
+
+        let riding = actors.is_riding(handle, self.uuid);
+
+        if let Some(push) = self.push_motion(previous, actor, motion, riding) {
+            Some(SolidInteraction {
+                actor: handle,
+                actor_uuid: actor.uuid,
+                carried: false,
+                motion: push
+            })
+        } else if riding {
+            Some(SolidInteraction {
+                actor: handle,
+                actor_uuid: actor.uuid,
+                carried: true,
+                motion: self.carry_motion(&actor.collider, motion)
+            })
+        } else {
+            None
+        }
+    }
+
+    /// Motion that pushes the actor out after the solid moved from `previous`, or None if it isn't pushed.
+    fn push_motion(&self, previous: &Collider, actor: &Actor, motion: Vec2I32, riding: bool) -> Option<Vec2I32> {
+        if self.tiles.is_some() {
+            return self.tile_solids(&actor.collider)
+                .filter_map(|tile| tile.push_motion(&tile.collider.moved(vec2i32(-motion.x, -motion.y)), actor, motion, riding))
+                .max_by_key(|push| push.x.abs() + push.y.abs());
+        }
+
+        // Riders of a slope overlap it whenever it moves towards them, but should be carried rather than pushed off.
+        let pushed = self.is_overlapping(vec2i32(0, 0), &actor.collider)
+            && self.pushes(previous, actor, motion)
+            && !(riding && self.slope.is_some());
+
+        pushed.then(|| self.push_out_motion(&actor.collider, motion))
+    }
+
+    /// Checks if the solid stops a collider moving by the offset. Normal solids always do, while one way solids
+    /// only block movement into their face by colliders that start fully outside of it.
+    /// Tile grids check the tiles the moved collider overlaps.
+    pub fn blocks(&self, collider: &Collider, offset: Vec2I32) -> bool {
+        if self.tiles.is_some() {
+            return self.tile_solids(&collider.moved(offset)).any(|tile| tile.is_overlapping(offset, collider) && tile.blocks(collider, offset));
+        }
+
+        match self.one_way {
+            Some(normal) => one_way_blocks(normal, &self.collider, collider, offset),
+            None => true
+        }
+    }
+
+    /// Checks if moving from `previous` by the motion should push the actor. One way solids only push actors
+    /// sitting outside their face when moving out through it.
+    fn pushes(&self, previous: &Collider, actor: &Actor, motion: Vec2I32) -> bool {
+        match self.one_way {
+            Some(normal) => !actor.is_dropping_through(self.uuid) && one_way_blocks(normal, previous, &actor.collider, vec2i32(-motion.x, -motion.y)),
+            None => true
+        }
     }
 
     pub fn tag(&mut self, tag: &str) {
@@ -41,73 +268,54 @@ impl Solid {
         self.tags.contains(&tag.to_string())
     }
 
-    pub(crate) fn move_x(&mut self, distance: f32, actors: &mut Vec<Actor>) -> Vec<SolidInteraction> {
+    /// Actors overlapping the solid or riding it, in storage order.
+    fn push_candidates(&self, actors: &ActorStorage) -> Vec<ActorHandle> {
+        let mut candidates = actors.query(&self.collider);
+        for rider in actors.riders_of(self.uuid) {
+            if !candidates.contains(rider) {
+                candidates.push(*rider);
+            }
+        }
+        candidates.sort_by_key(|handle| handle.index());
+        candidates
+    }
+
+    pub(crate) fn move_x(&mut self, distance: f32, actors: &ActorStorage) -> Vec<SolidInteraction> {
         self.remainder.x += distance;
+        self.moved |= distance != 0.0;
         let move_distance = self.remainder.x as i32;
         self.remainder.x -= move_distance as f32;
 
         let mut interactions = vec![];
 
         if move_distance != 0 {
+            let previous = self.collider.clone();
             self.collider.x += move_distance;
 
-            for actor in actors.iter_mut() {
-                if self.collider.is_overlapping(vec2i32(0, 0), &actor.collider) {
-                    if move_distance > 0 {
-                        interactions.push(SolidInteraction {
-                            actor_uuid: actor.uuid,
-                            motion: vec2i32(self.collider.max_x() - actor.collider.min_x(), 0)
-                        });
-                    } else {
-                        interactions.push(SolidInteraction {
-                            actor_uuid: actor.uuid,
-                            motion: vec2i32(self.collider.min_x() - actor.collider.max_x(), 0)
-                        });
-                    }
-                } else if actor.is_riding(self.uuid) {
-                    interactions.push(SolidInteraction {
-                        actor_uuid: actor.uuid,
-                        motion: vec2i32(move_distance, 0)
-                    })
-                }
+            for handle in self.push_candidates(actors) {
+                let actor = actors.get(handle).expect("broadphase returned a removed actor");
+                interactions.extend(self.interaction_with(&previous, handle, actor, actors, vec2i32(move_distance, 0)));
             }
         }
 
         interactions
     }
 
-    pub(crate) fn move_y(&mut self, distance: f32, actors: &mut Vec<Actor>) -> Vec<SolidInteraction> {
+    pub(crate) fn move_y(&mut self, distance: f32, actors: &ActorStorage) -> Vec<SolidInteraction> {
         self.remainder.y += distance;
+        self.moved |= distance != 0.0;
         let move_distance = self.remainder.y as i32;
         self.remainder.y -= move_distance as f32;
 
         let mut interactions = vec![];
 
         if move_distance != 0 {
+            let previous = self.collider.clone();
             self.collider.y += move_distance;
 
-            for actor in actors.iter_mut() {
-                if self.collider.is_overlapping(vec2i32(0, 0), &actor.collider) {
-                    if move_distance > 0 {
-                        interactions.push(SolidInteraction {
-                            actor_uuid: actor.uuid,
-                            motion: vec2i32(0, self.collider.max_y() - actor.collider.min_y())
-                        });
-                    }
-                    else {
-                        interactions.push(SolidInteraction {
-                            actor_uuid: actor.uuid,
-                            motion: vec2i32(0, self.collider.min_y() - actor.collider.max_y())
-                        });
-                    }
-
-                }
-                else if actor.is_riding(self.uuid) {
-                    interactions.push(SolidInteraction {
-                        actor_uuid: actor.uuid,
-                        motion: vec2i32(0, move_distance)
-                    })
-                }
+            for handle in self.push_candidates(actors) {
+                let actor = actors.get(handle).expect("broadphase returned a removed actor");
+                interactions.extend(self.interaction_with(&previous, handle, actor, actors, vec2i32(0, move_distance)));
             }
         }
 
@@ -117,6 +325,25 @@ impl Solid {
 
 #[derive(Debug, Copy, Clone)]
 pub struct SolidInteraction {
+    pub actor: ActorHandle,
     pub actor_uuid: Uuid,
+    /// True when the actor rides the solid rather than being pushed out of it.
+    pub carried: bool,
     pub motion: Vec2I32
-}
\ No newline at end of file
+}
+
+/// A one way solid blocks an offset that moves into its face, as long as the collider starts fully outside that face.
+fn one_way_blocks(normal: Vec2I32, solid: &Collider, collider: &Collider, offset: Vec2I32) -> bool {
+    let into_face = offset.x * normal.x + offset.y * normal.y < 0;
+    let outside = if normal.x > 0 {
+        collider.min_x() >= solid.max_x()
+    } else if normal.x < 0 {
+        collider.max_x() <= solid.min_x()
+    } else if normal.y > 0 {
+        collider.min_y() >= solid.max_y()
+    } else {
+        collider.max_y() <= solid.min_y()
+    };
+
+    into_face && outside
+}
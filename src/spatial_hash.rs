@@ -0,0 +1,156 @@
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+use crate::collider::Collider;
+
+/// Inclusive range of grid cells covered by a collider.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+struct CellBounds {
+    min_x: i32,
+    min_y: i32,
+    max_x: i32,
+    max_y: i32
+}
+
+impl CellBounds {
+    fn contains(&self, x: i32, y: i32) -> bool {
+        (self.min_x..=self.max_x).contains(&x) && (self.min_y..=self.max_y).contains(&y)
+    }
+}
+
+/// Uniform grid broadphase. Each key is stored in every cell its collider touches,
+/// so a query only has to look at the keys sharing a cell with the queried area.
+pub struct SpatialHash<K> {
+    cell_size: i32,
+    cells: HashMap<(i32, i32), Vec<K>>,
+    bounds: HashMap<K, CellBounds>
+}
+
+impl<K: Copy + Eq + Hash> SpatialHash<K> {
+    pub fn new(cell_size: i32) -> Self {
+        assert!(cell_size > 0, "spatial hash cell size must be positive, got {}", cell_size);
+
+        Self {
+            cell_size,
+            cells: HashMap::new(),
+            bounds: HashMap::new()
+        }
+    }
+
+    pub fn cell_size(&self) -> i32 {
+        self.cell_size
+    }
+
+    fn cell_bounds(&self, collider: &Collider) -> CellBounds {
+        // Max edges are exclusive, but zero sized colliders still have to live in one cell.
+        let min_x = collider.min_x().div_euclid(self.cell_size);
+        let min_y = collider.min_y().div_euclid(self.cell_size);
+        CellBounds {
+            min_x,
+            min_y,
+            max_x: (collider.max_x() - 1).div_euclid(self.cell_size).max(min_x),
+            max_y: (collider.max_y() - 1).div_euclid(self.cell_size).max(min_y)
+        }
+    }
+
+    /// Adds the key, or moves it if it is already stored.
+    pub fn insert(&mut self, key: K, collider: &Collider) {
+        let new_bounds = self.cell_bounds(collider);
+
+        if let Some(old_bounds) = self.bounds.get(&key).copied() {
+            if old_bounds == new_bounds {
+                return;
+            }
+            self.remove_from_cells(key, old_bounds);
+        }
+
+        for x in new_bounds.min_x..=new_bounds.max_x {
+            for y in new_bounds.min_y..=new_bounds.max_y {
+                self.cells.entry((x, y)).or_default().push(key);
+            }
+        }
+        self.bounds.insert(key, new_bounds);
+    }
+
+    pub fn remove(&mut self, key: K) {
+        if let Some(old_bounds) = self.bounds.remove(&key) {
+            self.remove_from_cells(key, old_bounds);
+        }
+    }
+
+    fn remove_from_cells(&mut self, key: K, bounds: CellBounds) {
+        for x in bounds.min_x..=bounds.max_x {
+            for y in bounds.min_y..=bounds.max_y {
+                if let Some(cell) = self.cells.get_mut(&(x, y)) {
+                    cell.retain(|stored| *stored != key);
+                    if cell.is_empty() {
+                        self.cells.remove(&(x, y));
+                    }
+                }
+            }
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.cells.clear();
+        self.bounds.clear();
+    }
+
+    /// Returns every key whose cells touch the area, each key once. This is a superset of the keys actually overlapping it.
+    /// Areas covering more cells than are in use, like long rays, look through the used cells instead of the area's,
+    /// so a query never costs more than a look at everything stored.
+    pub fn query(&self, area: &Collider) -> Vec<K> {
+        let area_bounds = self.cell_bounds(area);
+        let mut seen = HashSet::new();
+        let mut found = vec![];
+        let mut visit = |cell: &Vec<K>| {
+            for key in cell {
+                if seen.insert(*key) {
+                    found.push(*key);
+                }
+            }
+        };
+
+        let area_cells = (area_bounds.max_x as i64 - area_bounds.min_x as i64 + 1) * (area_bounds.max_y as i64 - area_bounds.min_y as i64 + 1);
+        if area_cells > self.cells.len() as i64 {
+            for ((x, y), cell) in self.cells.iter() {
+                if area_bounds.contains(*x, *y) {
+                    visit(cell);
+                }
+            }
+        } else {
+            for x in area_bounds.min_x..=area_bounds.max_x {
+                for y in area_bounds.min_y..=area_bounds.max_y {
+                    if let Some(cell) = self.cells.get(&(x, y)) {
+                        visit(cell);
+                    }
+                }
+            }
+        }
+
+        found
+    }
+}
+
+/// How the storages find collision candidates.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Broadphase {
+    /// Uniform grid with square cells of this size in pixels.
+    SpatialHash { cell_size: i32 },
+    /// Tests against every entity. Slow, but useful to verify the spatial hash.
+    BruteForce
+}
+
+impl Default for Broadphase {
+    fn default() -> Self {
+        Broadphase::SpatialHash { cell_size: 64 }
+    }
+}
+
+impl Broadphase {
+    pub(crate) fn grid<K: Copy + Eq + Hash>(&self) -> Option<SpatialHash<K>> {
+        match self {
+            Broadphase::SpatialHash { cell_size } => Some(SpatialHash::new(*cell_size)),
+            Broadphase::BruteForce => None
+        }
+    }
+}
@@ -0,0 +1,214 @@
+use std::collections::{HashMap, HashSet};
+use uuid::Uuid;
+use crate::arena::{Arena, Handle};
+use crate::collider::Collider;
+use crate::error::PhysicsError;
+use crate::spatial_hash::{Broadphase, SpatialHash};
+
+/// Something a [`Storage`] can hold: an entity with a UUID and a collider for the broadphase.
+pub trait Stored: Sized {
+    /// State the storage keeps next to its entities, like which actors ride which solids.
+    type Extra: Default;
+
+    fn uuid(&self) -> Uuid;
+
+    fn collider(&self) -> &Collider;
+
+    fn duplicate_error(uuid: Uuid) -> PhysicsError;
+
+    fn not_found_error(uuid: Uuid) -> PhysicsError;
+
+    fn despawned_error(uuid: Uuid) -> PhysicsError;
+
+    /// Forgets a removed entity in the extra state.
+    fn removed(_extra: &mut Self::Extra, _handle: Handle<Self>) { }
+}
+
+/// Stores entities in a generational arena, with a UUID index for O(1) lookups by UUID
+/// and a broadphase to find the ones in an area.
+///
+/// The UUID of every removed entity is remembered, so that looking it up reports that it was despawned rather than
+/// never spawned. That is one UUID per removal, so games that spawn and despawn a lot, like projectiles, should call
+/// [`Storage::forget_despawned`] now and then, e.g. between levels.
+///
+/// Entities handed out mutably may get their collider edited behind the broadphase's back, so they are always
+/// returned by queries until they are synced again. That keeps the spatial hash giving the same results as brute force.
+pub struct Storage<T: Stored> {
+    entities: Arena<T>,
+    uuid_index: HashMap<Uuid, Handle<T>>,
+    despawned: HashSet<Uuid>,
+    grid: Option<SpatialHash<Handle<T>>>,
+    /// Entities that may have moved since they were last synced.
+    unsynced: HashSet<Handle<T>>,
+    /// Set when every entity was handed out mutably at once.
+    all_unsynced: bool,
+    pub(crate) extra: T::Extra
+}
+
+impl<T: Stored> Default for Storage<T> {
+    fn default() -> Self {
+        Self::new(Broadphase::default())
+    }
+}
+
+impl<T: Stored> Storage<T> {
+    pub fn new(broadphase: Broadphase) -> Self {
+        Self {
+            entities: Arena::new(),
+            uuid_index: HashMap::new(),
+            despawned: HashSet::new(),
+            grid: broadphase.grid(),
+            unsynced: HashSet::new(),
+            all_unsynced: false,
+            extra: T::Extra::default()
+        }
+    }
+
+    /// Inserts an entity, failing if another one already uses its UUID.
+    pub fn insert(&mut self, entity: T) -> Result<Handle<T>, PhysicsError> {
+        let uuid = entity.uuid();
+        if self.uuid_index.contains_key(&uuid) {
+            return Err(T::duplicate_error(uuid));
+        }
+
+        let handle = self.entities.insert(entity);
+        self.uuid_index.insert(uuid, handle);
+        self.despawned.remove(&uuid);
+        self.sync(handle);
+
+        Ok(handle)
+    }
+
+    pub fn get_by_uuid(&mut self, uuid: Uuid) -> Result<&mut T, PhysicsError> {
+        match self.uuid_index.get(&uuid) {
+            Some(handle) => Ok(self.get_mut(*handle).expect("uuid index out of sync")),
+            None => Err(self.missing_error(uuid))
+        }
+    }
+
+    /// Removes the entity, invalidating its handle. Later lookups by its UUID report that it was despawned.
+    pub fn remove(&mut self, uuid: Uuid) -> Result<T, PhysicsError> {
+        match self.uuid_index.remove(&uuid) {
+            Some(handle) => {
+                self.despawned.insert(uuid);
+                if let Some(grid) = &mut self.grid {
+                    grid.remove(handle);
+                }
+                self.unsynced.remove(&handle);
+                T::removed(&mut self.extra, handle);
+                Ok(self.entities.remove(handle).expect("uuid index out of sync"))
+            }
+            None => Err(self.missing_error(uuid))
+        }
+    }
+
+    /// Returns true if the entity with this UUID existed but has since been despawned.
+    pub fn is_despawned(&self, uuid: Uuid) -> bool {
+        self.despawned.contains(&uuid)
+    }
+
+    /// Forgets the UUIDs of removed entities, after which looking them up reports them as never spawned.
+    pub fn forget_despawned(&mut self) {
+        self.despawned.clear();
+    }
+
+    pub(crate) fn missing_error(&self, uuid: Uuid) -> PhysicsError {
+        match self.is_despawned(uuid) {
+            true => T::despawned_error(uuid),
+            false => T::not_found_error(uuid)
+        }
+    }
+
+    pub fn handle_of(&self, uuid: Uuid) -> Option<Handle<T>> {
+        self.uuid_index.get(&uuid).copied()
+    }
+
+    pub fn get(&self, handle: Handle<T>) -> Option<&T> {
+        self.entities.get(handle)
+    }
+
+    pub fn get_mut(&mut self, handle: Handle<T>) -> Option<&mut T> {
+        if self.grid.is_some() && self.entities.get(handle).is_some() {
+            self.unsynced.insert(handle);
+        }
+        self.entities.get_mut(handle)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.entities.values()
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut T> {
+        self.all_unsynced = self.grid.is_some();
+        self.entities.values_mut()
+    }
+
+    pub fn iter_with_handles(&self) -> impl Iterator<Item = (Handle<T>, &T)> {
+        self.entities.iter()
+    }
+
+    pub fn handles(&self) -> impl Iterator<Item = Handle<T>> + '_ {
+        self.entities.iter().map(|(handle, _)| handle)
+    }
+
+    pub fn len(&self) -> usize {
+        self.entities.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entities.is_empty()
+    }
+
+    /// Removes every entity and forgets the despawned ones, leaving the storage as it was when created.
+    pub fn clear(&mut self) {
+        self.entities.clear();
+        self.uuid_index.clear();
+        self.despawned.clear();
+        self.unsynced.clear();
+        self.all_unsynced = false;
+        self.extra = T::Extra::default();
+        if let Some(grid) = &mut self.grid {
+            grid.clear();
+        }
+    }
+
+    /// Updates the broadphase entry of the entity after its collider changed.
+    pub fn sync(&mut self, handle: Handle<T>) {
+        if let (Some(grid), Some(entity)) = (&mut self.grid, self.entities.get(handle)) {
+            grid.insert(handle, entity.collider());
+        }
+        self.unsynced.remove(&handle);
+    }
+
+    /// Updates the broadphase entry of every entity, so queries stop returning the ones that were edited directly.
+    pub fn sync_all(&mut self) {
+        if let Some(grid) = &mut self.grid {
+            for (handle, entity) in self.entities.iter() {
+                grid.insert(handle, entity.collider());
+            }
+        }
+        self.unsynced.clear();
+        self.all_unsynced = false;
+    }
+
+    /// Switches between the spatial hash and brute force, rebuilding the grid if needed.
+    pub fn set_broadphase(&mut self, broadphase: Broadphase) {
+        self.grid = broadphase.grid();
+        self.sync_all();
+    }
+
+    /// Returns the handles of every entity that could overlap the area, in storage order.
+    /// With brute force this is every entity, and so it is after every entity was handed out by [`Storage::iter_mut`].
+    pub fn query(&self, area: &Collider) -> Vec<Handle<T>> {
+        match &self.grid {
+            Some(grid) if !self.all_unsynced => {
+                let mut found = grid.query(area);
+                found.extend(self.unsynced.iter().copied());
+                found.sort_by_key(|handle| handle.index());
+                found.dedup();
+                found
+            }
+            _ => self.handles().collect()
+        }
+    }
+}
@@ -0,0 +1,113 @@
+use crate::collider::Collider;
+use crate::error::PhysicsError;
+use crate::math::vec2i32;
+use crate::slope::Slope;
+
+/// What fills one cell of a [`TileGrid`].
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum Tile {
+    #[default]
+    Empty,
+    Solid,
+    /// A ledge that can be jumped through from below, like a one way solid with normal (0, 1).
+    OneWay,
+    /// A 45° slope filling the tile.
+    Slope(Slope)
+}
+
+/// A grid of tiles that collides like many solids while being stored as one.
+/// Give it to a solid through [`crate::solid::Solid::tiles`] or [`crate::engine::PhysicsEngine::spawn_tile_grid`].
+/// The solid's collider covers the whole grid and its position is the bottom left corner of tile (0, 0).
+/// Columns go right and rows go up.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TileGrid {
+    tile_size: i32,
+    columns: i32,
+    rows: i32,
+    tiles: Vec<Tile>
+}
+
+impl TileGrid {
+    /// Creates an empty grid.
+    pub fn new(tile_size: i32, columns: i32, rows: i32) -> Self {
+        assert!(tile_size > 0, "tile size must be positive, got {}", tile_size);
+
+        Self {
+            tile_size,
+            columns: columns.max(0),
+            rows: rows.max(0),
+            tiles: vec![Tile::Empty; (columns.max(0) * rows.max(0)) as usize]
+        }
+    }
+
+    pub fn tile_size(&self) -> i32 {
+        self.tile_size
+    }
+
+    pub fn columns(&self) -> i32 {
+        self.columns
+    }
+
+    pub fn rows(&self) -> i32 {
+        self.rows
+    }
+
+    /// The collider covering every tile when tile (0, 0) starts at the position.
+    pub fn bounds(&self, x: i32, y: i32) -> Collider {
+        Collider::new(x, y, self.columns * self.tile_size, self.rows * self.tile_size)
+    }
+
+    fn index(&self, column: i32, row: i32) -> Option<usize> {
+        match (0..self.columns).contains(&column) && (0..self.rows).contains(&row) {
+            true => Some((row * self.columns + column) as usize),
+            false => None
+        }
+    }
+
+    /// Returns the tile, or None outside the grid.
+    pub fn get(&self, column: i32, row: i32) -> Option<Tile> {
+        self.index(column, row).map(|index| self.tiles[index])
+    }
+
+    pub fn set(&mut self, column: i32, row: i32, tile: Tile) -> Result<(), PhysicsError> {
+        match self.index(column, row) {
+            Some(index) => {
+                self.tiles[index] = tile;
+                Ok(())
+            }
+            None => Err(PhysicsError::TileOutOfBounds { column, row })
+        }
+    }
+
+    pub fn clear(&mut self, column: i32, row: i32) -> Result<(), PhysicsError> {
+        self.set(column, row, Tile::Empty)
+    }
+
+    /// The collider of a tile when the grid's collider is `bounds`.
+    pub fn tile_collider(&self, bounds: &Collider, column: i32, row: i32) -> Collider {
+        Collider {
+            enabled: bounds.enabled,
+            ..Collider::new(bounds.x + column * self.tile_size, bounds.y + row * self.tile_size, self.tile_size, self.tile_size)
+        }
+    }
+
+    /// Every tile that isn't empty and shares a pixel with the area, with its column and row.
+    /// Only looks at the cells under the area, so small areas are cheap however big the grid is.
+    pub fn tiles_in<'a>(&'a self, bounds: &Collider, area: &Collider) -> impl Iterator<Item = (i32, i32, Tile)> + 'a {
+        let first = vec2i32(
+            (area.min_x() - bounds.x).div_euclid(self.tile_size).max(0),
+            (area.min_y() - bounds.y).div_euclid(self.tile_size).max(0)
+        );
+        let last = vec2i32(
+            (area.max_x() - 1 - bounds.x).div_euclid(self.tile_size).min(self.columns - 1),
+            (area.max_y() - 1 - bounds.y).div_euclid(self.tile_size).min(self.rows - 1)
+        );
+
+        (first.y..=last.y)
+            .flat_map(move |row| (first.x..=last.x).map(move |column| (column, row)))
+            .filter_map(move |(column, row)| match self.get(column, row) {
+                Some(Tile::Empty) | None => None,
+                Some(tile) => Some((column, row, tile))
+            })
+    }
+}
@@ -0,0 +1,93 @@
+use crate::engine::PhysicsEngine;
+use crate::error::PhysicsError;
+
+/// Frame times are f32, so frames that add up to exactly a tick can fall short of it by a rounding error.
+const TICK_EPSILON: f64 = 1e-6;
+
+/// Turns variable frame times into a whole number of fixed length ticks, so that the simulation
+/// comes out the same at any framerate. Leftover time carries over to the next frame.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FixedTimestep {
+    tick_dt: f64,
+    accumulator: f64,
+    /// The most ticks a single frame runs. Time beyond that is dropped, so one long frame can't make the next ones longer too.
+    pub max_ticks: u32
+}
+
+impl FixedTimestep {
+    /// Runs `tick_rate` ticks per second.
+    pub fn new(tick_rate: f32) -> Self {
+        assert!(tick_rate > 0.0, "tick rate must be positive, got {}", tick_rate);
+
+        Self {
+            tick_dt: 1.0 / tick_rate as f64,
+            accumulator: 0.0,
+            max_ticks: 8
+        }
+    }
+
+    pub fn tick_rate(&self) -> f32 {
+        (1.0 / self.tick_dt) as f32
+    }
+
+    /// Length of one tick in seconds.
+    pub fn tick_dt(&self) -> f32 {
+        self.tick_dt as f32
+    }
+
+    /// Adds the frame time and returns how many ticks are due.
+    pub fn advance(&mut self, dt: f32) -> u32 {
+        self.accumulator += dt.max(0.0) as f64;
+
+        let mut ticks = 0;
+        while ticks < self.max_ticks && self.accumulator + TICK_EPSILON >= self.tick_dt {
+            self.accumulator -= self.tick_dt;
+            ticks += 1;
+        }
+        if self.accumulator + TICK_EPSILON >= self.tick_dt {
+            self.accumulator = 0.0;
+        }
+
+        ticks
+    }
+
+    /// How far the time left over is into the next tick, from 0 to 1. Drawing entities at
+    /// `previous + (current - previous) * alpha` keeps motion smooth when frames and ticks don't line up.
+    pub fn alpha(&self) -> f32 {
+        (self.accumulator / self.tick_dt).clamp(0.0, 1.0) as f32
+    }
+
+    /// Drops the leftover time, e.g. after loading a level.
+    pub fn reset(&mut self) {
+        self.accumulator = 0.0;
+    }
+}
+
+impl Default for FixedTimestep {
+    /// 60 ticks per second.
+    fn default() -> Self {
+        Self::new(60.0)
+    }
+}
+
+impl PhysicsEngine {
+    /// Advances the simulation by the frame time in fixed ticks, see [`FixedTimestep`]. Every tick runs `tick` with
+    /// the tick length and then [`PhysicsEngine::end_update`]. Returns how many ticks ran, which is 0 on frames
+    /// shorter than a tick. Stops at the first tick that fails.
+    pub fn step<F: FnMut(&mut PhysicsEngine, f32) -> Result<(), PhysicsError>>(&mut self, dt: f32, mut tick: F) -> Result<u32, PhysicsError> {
+        let ticks = self.timestep.advance(dt);
+        let tick_dt = self.timestep.tick_dt();
+
+        for _ in 0..ticks {
+            tick(self, tick_dt)?;
+            self.end_update();
+        }
+
+        Ok(ticks)
+    }
+
+    /// How far rendering is between the last tick and the next one, see [`FixedTimestep::alpha`].
+    pub fn interpolation_alpha(&self) -> f32 {
+        self.timestep.alpha()
+    }
+}
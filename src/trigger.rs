@@ -0,0 +1,56 @@
+use glam::Vec2;
+use uuid::Uuid;
+use crate::layers::CollisionLayers;
+use crate::prelude::Collider;
+
+/// An area that reports actors entering, staying in and leaving it, without ever blocking them.
+/// Used for checkpoints, kill zones, doors and the like.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Trigger {
+    pub uuid: Uuid,
+    pub remainder: Vec2,
+    pub collider: Collider,
+    pub(crate) tags: Vec<String>,
+    /// Only actors whose layers collide with these are reported.
+    pub layers: CollisionLayers
+}
+
+impl Trigger {
+
+    /// Requires an ID. Id MUST be different than other TRIGGER's ID's.
+    pub fn new(collider: Collider, uuid: Uuid, tags: Option<Vec<String>>) -> Self {
+        Self {
+            uuid,
+            remainder: Default::default(),
+            collider,
+            tags: tags.unwrap_or_default(),
+            layers: CollisionLayers::DEFAULT
+        }
+    }
+
+    pub fn tag(&mut self, tag: &str) {
+        if !self.tags.contains(&tag.to_string()) {
+            self.tags.push(tag.to_string());
+        }
+    }
+
+    pub fn untag(&mut self, tag: &str) {
+        self.tags.retain(|x| x.as_str() != tag);
+    }
+
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.contains(&tag.to_string())
+    }
+
+    /// Moves by whole pixels, keeping the rest for later moves. Triggers pass through everything.
+    pub(crate) fn move_by(&mut self, distance: Vec2) {
+        self.remainder += distance;
+        let move_x = self.remainder.x as i32;
+        let move_y = self.remainder.y as i32;
+        self.remainder.x -= move_x as f32;
+        self.remainder.y -= move_y as f32;
+
+        self.collider.x += move_x;
+        self.collider.y += move_y;
+    }
+}
@@ -0,0 +1,94 @@
+use glam::vec2;
+use minimal_physics_engine::prelude::*;
+
+/// A wall at x = 40, a crate that blocks actors at x = 20 and a player at x = 0, all on the same row.
+fn row() -> (PhysicsEngine, uuid::Uuid, uuid::Uuid, uuid::Uuid) {
+    let mut engine = PhysicsEngine::new();
+    let wall = engine.spawn_solid(Collider::new(40, 0, 10, 20), None);
+    let pushed = engine.spawn_actor(Collider::new(20, 0, 8, 8));
+    engine.actor_storage.get_actor(pushed).unwrap().blocks_actors = true;
+    let player = engine.spawn_actor(Collider::new(0, 0, 8, 8));
+
+    (engine, wall, pushed, player)
+}
+
+#[test]
+fn pushes_actors_that_block_actors() {
+    let (mut engine, _, pushed, player) = row();
+
+    let result = engine.move_actor(player, vec2(20.0, 0.0)).unwrap();
+
+    assert_eq!(result.x, AxisMoveResult { travelled: 20, hit: None });
+    assert_eq!(engine.actor_storage.get_actor(pushed).unwrap().collider.x, 28);
+    assert_eq!(engine.drain_events().collect::<Vec<_>>(), vec![
+        PhysicsEvent::ActorPushedByActor { actor_uuid: pushed, pusher_uuid: player, motion: vec2i32(8, 0) }
+    ]);
+}
+
+#[test]
+fn stops_when_the_pushed_actor_is_pinned() {
+    let (mut engine, wall, pushed, player) = row();
+
+    let result = engine.move_actor(player, vec2(40.0, 0.0)).unwrap();
+
+    assert_eq!(result.x, AxisMoveResult { travelled: 24, hit: Some(CollisionInfo { target: CollisionTarget::Actor(pushed), normal: vec2i32(-1, 0), tags: vec![] }) });
+    let pinned = engine.actor_storage.get_actor(pushed).unwrap();
+    assert_eq!(pinned.collider.x, 32);
+    assert!(!pinned.squished);
+    assert!(engine.drain_events().any(|event| event == PhysicsEvent::ActorBlocked {
+        actor_uuid: pushed,
+        collision: CollisionInfo { target: CollisionTarget::Solid(wall), normal: vec2i32(-1, 0), tags: vec![] }
+    }));
+}
+
+#[test]
+fn custom_callbacks_only_run_for_the_mover() {
+    let (mut engine, _, pushed, player) = row();
+
+    let mut seen = vec![];
+    let mut record = |actor: &mut Actor, info: &CollisionInfo| seen.push((actor.uuid, info.target));
+    engine.move_actor_with_callback(player, vec2(40.0, 0.0), CollisionCallback::Custom(&mut record)).unwrap();
+
+    assert_eq!(seen, vec![(player, CollisionTarget::Actor(pushed))]);
+}
+
+#[test]
+fn solids_pushing_a_chain_of_actors_into_a_wall_squish_them() {
+    let (mut engine, wall, pushed, player) = row();
+    let piston = engine.spawn_solid(Collider::new(-20, 0, 20, 20), None);
+
+    for _ in 0..30 {
+        engine.move_solid(piston, vec2(1.0, 0.0)).unwrap();
+    }
+
+    assert!(engine.check_squished(pushed).unwrap());
+    assert!(engine.check_squished(player).unwrap());
+    let events: Vec<PhysicsEvent> = engine.drain_events().collect();
+    assert!(events.contains(&PhysicsEvent::ActorSquished {
+        actor_uuid: pushed,
+        solid_uuid: piston,
+        collision: CollisionInfo { target: CollisionTarget::Solid(wall), normal: vec2i32(-1, 0), tags: vec![] }
+    }));
+}
+
+#[test]
+fn actors_can_still_move_against_solids_alone() {
+    let (mut engine, wall, _, _) = row();
+    let mut actor = Actor::new(Collider::new(0, 0, 8, 8), uuid::Uuid::new_v4());
+
+    // The crate doesn't block an actor that isn't moved through the engine.
+    let result = actor.move_exact(vec2i32(50, 0), &mut CollisionCallback::None, &engine.solid_storage);
+    assert_eq!(result.x.travelled, 32);
+    assert_eq!(result.x.hit.and_then(|hit| hit.solid_uuid()), Some(wall));
+    assert_eq!(actor.collider.x, 32);
+
+    // Walking into a slope climbs it, just like moving through the engine.
+    engine.spawn_slope(Collider::new(-40, 0, 20, 20), Slope::UpLeft, None);
+    let through_engine = engine.spawn_actor(Collider::new(-10, 0, 8, 8));
+    engine.move_actor(through_engine, vec2(-20.0, 0.0)).unwrap();
+    let mut alone = Actor::new(Collider::new(-10, 0, 8, 8), uuid::Uuid::new_v4());
+    alone.move_x_exact(-20, &mut CollisionCallback::None, &engine.solid_storage);
+
+    assert_eq!(alone.collider, engine.actor_storage.get_actor(through_engine).unwrap().collider);
+    assert_eq!((alone.collider.x, alone.collider.y), (-30, 10));
+}
@@ -0,0 +1,111 @@
+mod common;
+
+use glam::vec2;
+use minimal_physics_engine::prelude::*;
+use common::Lcg;
+use uuid::Uuid;
+
+/// Two engines holding the same entities, one finding candidates by brute force and one with a spatial hash.
+struct Pair {
+    brute_force: PhysicsEngine,
+    spatial_hash: PhysicsEngine
+}
+
+impl Pair {
+    fn spawn_solid(&mut self, collider: Collider) -> Uuid {
+        let uuid = self.brute_force.spawn_solid(collider.clone(), None);
+        self.spatial_hash.solid_storage.insert(Solid::new(collider, uuid, None)).unwrap();
+        uuid
+    }
+
+    fn spawn_actor(&mut self, collider: Collider, blocks_actors: bool) -> Uuid {
+        let uuid = self.brute_force.spawn_actor(collider.clone());
+        self.spatial_hash.actor_storage.insert(Actor::new(collider, uuid)).unwrap();
+        for engine in [&mut self.brute_force, &mut self.spatial_hash] {
+            let actor = engine.actor_storage.get_actor(uuid).unwrap();
+            actor.blocks_actors = blocks_actors;
+            actor.ride_mode = RideMode::Standing;
+        }
+        uuid
+    }
+}
+
+#[test]
+fn brute_force_and_spatial_hash_agree() {
+    // Small cells so most entities span several of them, including across negative coordinates.
+    let mut pair = Pair {
+        brute_force: PhysicsEngine::with_broadphase(Broadphase::BruteForce),
+        spatial_hash: PhysicsEngine::with_broadphase(Broadphase::SpatialHash { cell_size: 12 })
+    };
+    let mut random = Lcg(11);
+
+    pair.spawn_solid(Collider::new(-200, -220, 400, 20));
+    pair.spawn_solid(Collider::new(-220, -220, 20, 440));
+    pair.spawn_solid(Collider::new(200, -220, 20, 440));
+    for _ in 0..30 {
+        pair.spawn_solid(Collider::new(random.range(-190, 170), random.range(-190, 170), random.range(4, 40), random.range(4, 20)));
+    }
+    let platforms: Vec<Uuid> = (0..4)
+        .map(|_| pair.spawn_solid(Collider::new(random.range(-150, 110), random.range(-150, 110), random.range(16, 48), 6)))
+        .collect();
+
+    let mut actors = vec![];
+    for index in 0..12 {
+        let collider = Collider::new(random.range(-190, 180), random.range(-190, 180), random.range(4, 10), random.range(6, 14));
+        actors.push(pair.spawn_actor(collider, index % 3 == 0));
+    }
+
+    for frame in 0..600 {
+        for (index, platform) in platforms.iter().enumerate() {
+            let phase = (frame / 60 + index) % 2;
+            let speed = random.range(0, 300) as f32 / 100.0;
+            let distance = match phase {
+                0 => vec2(speed, speed * 0.5),
+                _ => vec2(-speed, -speed * 0.5)
+            };
+            pair.brute_force.move_solid(*platform, distance).unwrap();
+            pair.spatial_hash.move_solid(*platform, distance).unwrap();
+        }
+        for actor in actors.iter() {
+            let distance = vec2(random.range(-400, 400) as f32 / 100.0, random.range(-500, 200) as f32 / 100.0);
+            let brute_force = pair.brute_force.move_actor(*actor, distance).unwrap();
+            let spatial_hash = pair.spatial_hash.move_actor(*actor, distance).unwrap();
+            assert_eq!(brute_force, spatial_hash, "frame {}", frame);
+        }
+        pair.brute_force.end_update();
+        pair.spatial_hash.end_update();
+
+        for actor in actors.iter() {
+            let brute_force = pair.brute_force.actor_storage.get_actor(*actor).unwrap().clone();
+            let spatial_hash = pair.spatial_hash.actor_storage.get_actor(*actor).unwrap();
+            assert_eq!(brute_force.collider, spatial_hash.collider, "frame {}", frame);
+            assert_eq!(brute_force.remainder, spatial_hash.remainder, "frame {}", frame);
+            assert_eq!(brute_force.squished, spatial_hash.squished, "frame {}", frame);
+        }
+        assert_eq!(pair.brute_force.actor_storage.riding_pairs(), pair.spatial_hash.actor_storage.riding_pairs(), "frame {}", frame);
+        assert_eq!(pair.brute_force.drain_events().collect::<Vec<_>>(), pair.spatial_hash.drain_events().collect::<Vec<_>>(), "frame {}", frame);
+    }
+}
+
+#[test]
+fn colliders_edited_directly_are_still_found() {
+    let mut pair = Pair {
+        brute_force: PhysicsEngine::with_broadphase(Broadphase::BruteForce),
+        spatial_hash: PhysicsEngine::with_broadphase(Broadphase::SpatialHash { cell_size: 16 })
+    };
+    let wall = pair.spawn_solid(Collider::new(0, 0, 10, 10));
+    let actor = pair.spawn_actor(Collider::new(200, 0, 8, 8), false);
+    let solid = pair.spawn_solid(Collider::new(300, 0, 8, 8));
+
+    for engine in [&mut pair.brute_force, &mut pair.spatial_hash] {
+        engine.actor_storage.get_actor(actor).unwrap().collider.x = 12;
+        engine.solid_storage.get_solid(solid).unwrap().collider.x = 30;
+        engine.move_solid(wall, vec2(5.0, 0.0)).unwrap();
+        assert_eq!(engine.actor_storage.get_actor(actor).unwrap().collider.x, 15);
+
+        // The edited solid blocks the actor before the broadphase was synced at the end of the frame.
+        let result = engine.move_actor(actor, vec2(20.0, 0.0)).unwrap();
+        assert_eq!(result.x.hit.and_then(|hit| hit.solid_uuid()), Some(solid));
+        assert_eq!(engine.query_point(vec2i32(31, 1), &QueryFilter::default()).solids, vec![solid]);
+    }
+}
@@ -0,0 +1,71 @@
+use glam::vec2;
+use minimal_physics_engine::prelude::*;
+use uuid::Uuid;
+
+/// A tagged wall at x = 20 standing on a floor at y = 0, and an actor left of the wall above the floor.
+fn corner() -> (PhysicsEngine, Uuid, Uuid, Uuid) {
+    let mut engine = PhysicsEngine::new();
+    let wall = engine.spawn_solid(Collider::new(20, 0, 10, 40), Some(vec!["wall".to_string()]));
+    let floor = engine.spawn_solid(Collider::new(-20, -10, 60, 10), None);
+    let actor = engine.spawn_actor(Collider::new(0, 10, 8, 8));
+
+    (engine, wall, floor, actor)
+}
+
+#[test]
+fn custom_callbacks_see_every_hit() {
+    let (mut engine, wall, floor, actor) = corner();
+
+    let mut hits = vec![];
+    let mut record = |actor: &mut Actor, info: &CollisionInfo| hits.push((actor.uuid, info.clone()));
+    engine.move_actor_with_callback(actor, vec2(40.0, -40.0), CollisionCallback::Custom(&mut record)).unwrap();
+
+    assert_eq!(hits, vec![
+        (actor, CollisionInfo { target: CollisionTarget::Solid(wall), normal: vec2i32(-1, 0), tags: vec!["wall".to_string()] }),
+        (actor, CollisionInfo { target: CollisionTarget::Solid(floor), normal: vec2i32(0, 1), tags: vec![] })
+    ]);
+}
+
+#[test]
+fn custom_callbacks_can_change_the_actor() {
+    let (mut engine, _, _, actor) = corner();
+
+    // Bounce off walls by starting the next move half a pixel back out along the normal.
+    let mut bounce = |actor: &mut Actor, info: &CollisionInfo| actor.remainder.x = info.normal.x as f32 * 0.5;
+    engine.move_actor_with_callback(actor, vec2(20.0, 0.0), CollisionCallback::Custom(&mut bounce)).unwrap();
+
+    let bounced = engine.actor_storage.get_actor(actor).unwrap();
+    assert_eq!((bounced.collider.x, bounced.remainder.x), (12, -0.5));
+    assert!(!bounced.squished);
+}
+
+#[test]
+fn custom_callbacks_replace_squishing_for_solid_pushes() {
+    let (mut engine, wall, _, actor) = corner();
+    let piston = engine.spawn_solid(Collider::new(-10, 10, 10, 10), None);
+
+    let mut hits = vec![];
+    let mut record = |actor: &mut Actor, info: &CollisionInfo| hits.push((actor.uuid, info.target));
+    for _ in 0..15 {
+        engine.move_solid_with_callback(piston, vec2(1.0, 0.0), CollisionCallback::Custom(&mut record)).unwrap();
+    }
+
+    // The actor reaches the wall after 12 pixels, then gets pushed into it three more times.
+    assert_eq!(hits, vec![(actor, CollisionTarget::Solid(wall)); 3]);
+    assert!(!engine.check_squished(actor).unwrap());
+    assert!(engine.drain_events().any(|event| matches!(event, PhysicsEvent::ActorBlocked { actor_uuid, .. } if actor_uuid == actor)));
+}
+
+#[test]
+fn built_in_callbacks() {
+    let (mut engine, _, _, actor) = corner();
+
+    engine.move_actor_with_callback(actor, vec2(20.0, 0.0), CollisionCallback::None).unwrap();
+    assert!(!engine.check_squished(actor).unwrap());
+
+    engine.move_actor_with_callback(actor, vec2(0.0, 5.0), CollisionCallback::Squish).unwrap();
+    assert!(!engine.check_squished(actor).unwrap(), "moves that aren't blocked don't squish");
+
+    engine.move_actor_with_callback(actor, vec2(20.0, 0.0), CollisionCallback::Squish).unwrap();
+    assert!(engine.check_squished(actor).unwrap());
+}
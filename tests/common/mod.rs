@@ -0,0 +1,9 @@
+/// Small deterministic generator so failures can be replayed.
+pub struct Lcg(pub u64);
+
+impl Lcg {
+    pub fn range(&mut self, min: i32, max: i32) -> i32 {
+        self.0 = self.0.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+        min + ((self.0 >> 33) % (max - min + 1) as u64) as i32
+    }
+}
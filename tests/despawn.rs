@@ -0,0 +1,31 @@
+use minimal_physics_engine::prelude::*;
+
+#[test]
+fn forgotten_despawns_are_not_found() {
+    let mut engine = PhysicsEngine::new();
+    let actor = engine.spawn_actor(Collider::new(0, 0, 8, 8));
+    let solid = engine.spawn_solid(Collider::new(0, -16, 32, 16), None);
+    engine.despawn_actor(actor).unwrap();
+    engine.despawn_solid(solid).unwrap();
+
+    assert_eq!(engine.despawn_actor(actor).unwrap_err(), PhysicsError::ActorDespawned(actor));
+    assert_eq!(engine.despawn_solid(solid).unwrap_err(), PhysicsError::SolidDespawned(solid));
+
+    engine.forget_despawned();
+
+    assert_eq!(engine.despawn_actor(actor).unwrap_err(), PhysicsError::ActorNotFound(actor));
+    assert_eq!(engine.despawn_solid(solid).unwrap_err(), PhysicsError::SolidNotFound(solid));
+}
+
+#[test]
+fn clearing_a_storage_forgets_its_despawns() {
+    let mut engine = PhysicsEngine::new();
+    let despawned = engine.spawn_actor(Collider::new(0, 0, 8, 8));
+    engine.spawn_actor(Collider::new(16, 0, 8, 8));
+    engine.despawn_actor(despawned).unwrap();
+
+    engine.actor_storage.clear();
+
+    assert!(!engine.actor_storage.is_despawned(despawned));
+    assert_eq!(engine.actor_storage.get_by_uuid(despawned).unwrap_err(), PhysicsError::ActorNotFound(despawned));
+}
@@ -0,0 +1,65 @@
+use std::error::Error;
+use glam::vec2;
+use minimal_physics_engine::prelude::*;
+use uuid::Uuid;
+
+#[test]
+fn unknown_uuids_name_what_was_missing() {
+    let mut engine = PhysicsEngine::new();
+    let unknown = Uuid::new_v4();
+
+    assert_eq!(engine.move_actor(unknown, vec2(1.0, 0.0)).unwrap_err(), PhysicsError::ActorNotFound(unknown));
+    assert_eq!(engine.move_solid(unknown, vec2(1.0, 0.0)).unwrap_err(), PhysicsError::SolidNotFound(unknown));
+    assert_eq!(engine.move_trigger(unknown, vec2(1.0, 0.0)).unwrap_err(), PhysicsError::TriggerNotFound(unknown));
+    assert_eq!(engine.check_squished(unknown).unwrap_err(), PhysicsError::ActorNotFound(unknown));
+    assert_eq!(engine.get_actors_in_trigger(unknown).unwrap_err(), PhysicsError::TriggerNotFound(unknown));
+}
+
+#[test]
+fn despawned_entities_and_stale_handles() {
+    let mut engine = PhysicsEngine::new();
+    let actor = engine.spawn_actor(Collider::new(0, 0, 8, 8));
+    let solid = engine.spawn_solid(Collider::new(0, -16, 32, 16), None);
+    let trigger = engine.spawn_trigger(Collider::new(0, 0, 8, 8), None);
+    let handles = (engine.actor_handle(actor).unwrap(), engine.solid_handle(solid).unwrap(), engine.trigger_handle(trigger).unwrap());
+    engine.despawn_actor(actor).unwrap();
+    engine.despawn_solid(solid).unwrap();
+    engine.despawn_trigger(trigger).unwrap();
+
+    assert_eq!(engine.move_actor(actor, vec2(1.0, 0.0)).unwrap_err(), PhysicsError::ActorDespawned(actor));
+    assert_eq!(engine.move_solid(solid, vec2(1.0, 0.0)).unwrap_err(), PhysicsError::SolidDespawned(solid));
+    assert_eq!(engine.move_trigger(trigger, vec2(1.0, 0.0)).unwrap_err(), PhysicsError::TriggerDespawned(trigger));
+
+    assert_eq!(engine.move_actor_by_handle(handles.0, vec2(1.0, 0.0), CollisionCallback::None).unwrap_err(), PhysicsError::StaleActorHandle(handles.0));
+    assert_eq!(engine.move_solid_by_handle(handles.1, vec2(1.0, 0.0), CollisionCallback::None).unwrap_err(), PhysicsError::StaleSolidHandle(handles.1));
+    assert_eq!(engine.move_trigger_by_handle(handles.2, vec2(1.0, 0.0)).unwrap_err(), PhysicsError::StaleTriggerHandle(handles.2));
+}
+
+#[test]
+fn inserting_a_uuid_twice_is_a_duplicate() {
+    let mut engine = PhysicsEngine::new();
+    let actor = engine.spawn_actor(Collider::new(0, 0, 8, 8));
+    let solid = engine.spawn_solid(Collider::new(0, -16, 32, 16), None);
+
+    assert_eq!(engine.actor_storage.insert(Actor::new(Collider::new(0, 0, 8, 8), actor)).unwrap_err(), PhysicsError::DuplicateActor(actor));
+    assert_eq!(engine.solid_storage.insert(Solid::new(Collider::new(0, 0, 8, 8), solid, None)).unwrap_err(), PhysicsError::DuplicateSolid(solid));
+}
+
+#[test]
+fn errors_describe_themselves() {
+    let uuid = Uuid::nil();
+    assert_eq!(PhysicsError::ActorNotFound(uuid).to_string(), format!("Expected an actor with uuid {}, but did not find one.", uuid));
+    assert_eq!(
+        PhysicsError::LevelParse { line: 3, column: 14, message: "expected `,`".to_string() }.to_string(),
+        "Level did not have the correct formatting at line 3, column 14: expected `,`"
+    );
+
+    let entry = PhysicsError::LevelEntry { section: "level_solids".to_string(), index: 2, error: Box::new(PhysicsError::InvalidTileSize(0)) };
+    assert_eq!(entry.to_string(), "In level_solids[2]: Level contained a tile grid with tile size 0.");
+    assert_eq!(entry.source().map(|source| source.to_string()), Some(PhysicsError::InvalidTileSize(0).to_string()));
+
+    let source = Uuid::parse_str("not a uuid").unwrap_err();
+    let invalid = PhysicsError::InvalidUuid { value: "not a uuid".to_string(), source: source.clone() };
+    assert_eq!(invalid.source().map(|source| source.to_string()), Some(source.to_string()));
+    assert!(PhysicsError::SolidNotFound(uuid).source().is_none());
+}
@@ -0,0 +1,75 @@
+use minimal_physics_engine::expanded_engine::prelude::*;
+use minimal_physics_engine::prelude::*;
+use glam::vec2;
+
+/// An engine with a floor at y = 0 and an actor falling from above it.
+fn falling_actor(velocity: Velocity) -> (PhysicsEngine, uuid::Uuid) {
+    let mut engine = PhysicsEngine::new();
+    engine.set_gravity(vec2(0.0, -600.0));
+    engine.spawn_solid(Collider::new(-100, -10, 200, 10), None);
+    let actor = engine.spawn_actor(Collider::new(0, 100, 8, 8));
+    engine.set_actor_velocity(actor, velocity).unwrap();
+
+    (engine, actor)
+}
+
+#[test]
+fn landing_stops_the_fall() {
+    let (mut engine, actor) = falling_actor(Velocity::default());
+
+    for _ in 0..120 {
+        engine.integrate(1.0 / 60.0).unwrap();
+    }
+
+    assert_eq!(engine.actor_storage.get_actor(actor).unwrap().collider.y, 0);
+    // Zeroed whenever the floor blocks it, so it never builds up more than a pixel's worth of speed.
+    assert!(engine.actor_velocity(actor).unwrap().velocity.y > -60.0);
+}
+
+#[test]
+fn walls_stop_only_the_blocked_axis() {
+    let (mut engine, actor) = falling_actor(Velocity::new(vec2(300.0, 0.0)));
+    engine.spawn_solid(Collider::new(20, 0, 10, 200), None);
+
+    for _ in 0..30 {
+        engine.integrate(1.0 / 60.0).unwrap();
+    }
+
+    let velocity = engine.actor_velocity(actor).unwrap();
+    assert_eq!(velocity.velocity.x, 0.0);
+    assert!(velocity.velocity.y < 0.0);
+}
+
+#[test]
+fn falls_no_faster_than_terminal_velocity() {
+    let (mut engine, actor) = falling_actor(Velocity { terminal_velocity: 50.0, ..Velocity::new(vec2(10.0, 0.0)) });
+
+    for _ in 0..10 {
+        engine.integrate(1.0 / 60.0).unwrap();
+    }
+
+    let velocity = engine.actor_velocity(actor).unwrap();
+    assert_eq!(velocity.velocity, vec2(10.0, -50.0));
+}
+
+#[test]
+fn gravity_scale_applies_per_actor() {
+    let (mut engine, actor) = falling_actor(Velocity { gravity_scale: 0.5, ..Default::default() });
+
+    engine.integrate(0.1).unwrap();
+
+    assert!((engine.actor_velocity(actor).unwrap().velocity.y + 30.0).abs() < 1e-4);
+}
+
+#[test]
+fn solids_ignore_gravity() {
+    let (mut engine, _) = falling_actor(Velocity::default());
+    let platform = engine.spawn_solid(Collider::new(200, 50, 32, 8), None);
+    engine.set_solid_velocity(platform, Velocity::new(vec2(60.0, 0.0))).unwrap();
+
+    for _ in 0..60 {
+        engine.integrate(1.0 / 60.0).unwrap();
+    }
+
+    assert_eq!(engine.solid_storage.get_solid(platform).unwrap().collider, Collider::new(260, 50, 32, 8));
+}
@@ -0,0 +1,76 @@
+use glam::vec2;
+use minimal_physics_engine::prelude::*;
+
+const PLAYER: CollisionLayers = CollisionLayers { layer: 1, mask: 1 };
+const ENEMY: CollisionLayers = CollisionLayers { layer: 4, mask: u32::MAX };
+/// Only blocks enemies, the player's mask leaves it out.
+const ENEMY_WALL: CollisionLayers = CollisionLayers { layer: 2, mask: u32::MAX };
+
+#[test]
+fn masked_solids_do_not_block_movement() {
+    let mut engine = PhysicsEngine::new();
+    let wall = engine.spawn_solid_with_layers(Collider::new(20, 0, 10, 20), None, ENEMY_WALL);
+    let player = engine.spawn_actor_with_layers(Collider::new(0, 0, 8, 8), PLAYER);
+    let enemy = engine.spawn_actor_with_layers(Collider::new(0, 10, 8, 8), ENEMY);
+
+    let result = engine.move_actor(player, vec2(40.0, 0.0)).unwrap();
+    assert_eq!(result.x, AxisMoveResult { travelled: 40, hit: None });
+
+    let result = engine.move_actor(enemy, vec2(40.0, 0.0)).unwrap();
+    assert_eq!(result.x.travelled, 12);
+    assert_eq!(result.x.hit.and_then(|hit| hit.solid_uuid()), Some(wall));
+    assert!(!engine.check_overlapping_solid(player, vec2i32(-20, 0)).unwrap());
+}
+
+#[test]
+fn masked_actors_are_not_pushed_by_actors() {
+    let mut engine = PhysicsEngine::new();
+    let crate_uuid = engine.spawn_actor_with_layers(Collider::new(20, 0, 8, 8), ENEMY);
+    engine.actor_storage.get_actor(crate_uuid).unwrap().blocks_actors = true;
+    let player = engine.spawn_actor_with_layers(Collider::new(0, 0, 8, 8), PLAYER);
+
+    let result = engine.move_actor(player, vec2(30.0, 0.0)).unwrap();
+
+    assert_eq!(result.x, AxisMoveResult { travelled: 30, hit: None });
+    assert_eq!(engine.actor_storage.get_actor(crate_uuid).unwrap().collider.x, 20);
+}
+
+#[test]
+fn masked_solids_are_not_ridden_or_pushing() {
+    let mut engine = PhysicsEngine::new();
+    let platform = engine.spawn_solid_with_layers(Collider::new(0, -10, 40, 10), None, ENEMY_WALL);
+    let piston = engine.spawn_solid_with_layers(Collider::new(-20, 0, 10, 10), None, ENEMY_WALL);
+    let player = engine.spawn_actor_with_layers(Collider::new(10, 0, 8, 8), PLAYER);
+    engine.actor_storage.get_actor(player).unwrap().ride_mode = RideMode::Standing;
+    engine.end_update();
+
+    engine.move_solid(platform, vec2(5.0, 0.0)).unwrap();
+    engine.move_solid(piston, vec2(25.0, 0.0)).unwrap();
+    engine.end_update();
+
+    let actor = engine.actor_storage.get_actor(player).unwrap();
+    assert_eq!((actor.collider.x, actor.collider.y), (10, 0));
+    assert!(!actor.squished);
+    assert!(engine.actor_storage.riding_pairs().is_empty());
+    assert!(engine.drain_events().all(|event| !matches!(event, PhysicsEvent::ActorPushed { .. } | PhysicsEvent::ActorStartedRiding { .. })));
+}
+
+#[test]
+fn query_masks_skip_other_layers() {
+    let mut engine = PhysicsEngine::new();
+    let wall = engine.spawn_solid_with_layers(Collider::new(20, 0, 10, 20), None, ENEMY_WALL);
+    let floor = engine.spawn_solid(Collider::new(40, 0, 10, 20), None);
+    let enemy = engine.spawn_actor_with_layers(Collider::new(0, 0, 8, 8), ENEMY);
+    let player_mask = QueryFilter { mask: PLAYER.mask, ..Default::default() };
+    let area = Collider::new(0, 0, 60, 10);
+
+    assert_eq!(engine.query_rect(&area, &player_mask), QueryResult { solids: vec![floor], actors: vec![] });
+    assert_eq!(engine.query_rect(&area, &QueryFilter::default()), QueryResult { solids: vec![wall, floor], actors: vec![enemy] });
+    assert!(engine.query_point(vec2i32(25, 5), &player_mask).is_empty());
+    assert!(engine.query_circle(vec2(4.0, 4.0), 2.0, &player_mask).is_empty());
+
+    let hit = engine.raycast(vec2(0.0, 5.0), vec2(1.0, 0.0), 100.0, &player_mask).unwrap();
+    assert_eq!((hit.solid_uuid, hit.distance), (floor, 40.0));
+    let hit = engine.shape_cast(&Collider::new(0, 10, 8, 8), vec2i32(60, 0), &player_mask).unwrap();
+    assert_eq!((hit.solid_uuid, hit.offset), (floor, vec2i32(32, 0)));
+}
@@ -0,0 +1,160 @@
+use minimal_physics_engine::expanded_engine::json_loading::*;
+use minimal_physics_engine::prelude::*;
+
+fn load(json: &str, strict: bool) -> Result<(PhysicsEngine, Vec<PhysicsError>), PhysicsError> {
+    let mut engine = PhysicsEngine::new();
+    let warnings = load_level_from_str(&mut engine, json, LoadOptions { strict, ..Default::default() })?;
+    Ok((engine, warnings))
+}
+
+#[test]
+fn unversioned_levels_are_upgraded() {
+    let (engine, warnings) = load(r#"{"level_solids": [{"x": 0, "y": 0, "width": 16, "height": 16, "tags": null, "uuid": null}]}"#, true).unwrap();
+
+    assert!(warnings.is_empty());
+    assert_eq!(engine.solid_storage.len(), 1);
+}
+
+#[test]
+fn newer_versions_are_rejected() {
+    let result = load(r#"{"format_version": 99, "level_solids": []}"#, false);
+
+    assert!(matches!(result, Err(PhysicsError::UnsupportedLevelVersion { found: 99, supported: LEVEL_FORMAT_VERSION })));
+}
+
+#[test]
+fn unknown_fields_are_warnings_when_lenient() {
+    let json = r#"{"format_version": 1, "author": "me", "level_solids": [
+        {"x": 0, "y": 0, "width": 16, "height": 16, "uuid": null},
+        {"x": 16, "y": 0, "width": 16, "height": 16, "uuid": null, "colour": "red"}
+    ]}"#;
+    let (engine, warnings) = load(json, false).unwrap();
+
+    assert_eq!(engine.solid_storage.len(), 2);
+    assert_eq!(warnings, vec![
+        PhysicsError::UnknownLevelField("author".to_string()),
+        PhysicsError::LevelEntry { section: "level_solids".to_string(), index: 1, error: Box::new(PhysicsError::UnknownLevelField("colour".to_string())) }
+    ]);
+}
+
+#[test]
+fn unknown_fields_are_errors_when_strict() {
+    let json = r#"{"format_version": 1, "level_solids": [], "level_triggers": [
+        {"x": 0, "y": 0, "width": 16, "height": 16, "uuid": null, "colour": "red"}
+    ]}"#;
+    let result = load(json, true);
+
+    assert_eq!(result.err(), Some(PhysicsError::LevelEntry {
+        section: "level_triggers".to_string(),
+        index: 0,
+        error: Box::new(PhysicsError::UnknownLevelField("colour".to_string()))
+    }));
+}
+
+#[test]
+fn errors_point_at_the_entry() {
+    let json = r#"{"level_solids": [
+        {"x": 0, "y": 0, "width": 16, "height": 16, "uuid": null},
+        {"x": 0, "y": 0, "width": "wide", "height": 16, "uuid": null}
+    ]}"#;
+
+    match load(json, false) {
+        Err(error @ PhysicsError::LevelEntry { index: 1, .. }) => assert!(error.to_string().starts_with("In level_solids[1]:")),
+        result => panic!("expected an error for the second solid, got {:?}", result.err())
+    }
+
+    let json = r##"{"level_solids": [], "level_tile_grids": [{"x": 0, "y": 0, "tile_size": 8, "rows": ["#?"], "uuid": null}]}"##;
+    assert!(matches!(load(json, false).err(), Some(PhysicsError::LevelEntry { index: 0, error, .. }) if *error == PhysicsError::InvalidTile { value: '?', column: 1, row: 0 }));
+}
+
+#[test]
+fn wrong_field_types_say_where_they_are() {
+    let json = "{\"level_solids\": [],\n  \"level_actors\": [\n    {\"x\": 0, \"y\": 0, \"width\": 8, \"height\": 8, \"uuid\": null},\n    {\"x\": 0, \"y\": 0, \"width\": \"wide\", \"height\": 8, \"uuid\": null}\n  ]\n}";
+
+    match load(json, false) {
+        Err(PhysicsError::LevelEntry { section, index: 1, error }) => {
+            assert_eq!(section, "level_actors");
+            // The column of the closing quote of "wide".
+            assert!(matches!(*error, PhysicsError::LevelParse { line: 4, column: 36, .. }), "{:?}", error);
+        }
+        result => panic!("expected an error for the second actor, got {:?}", result.err())
+    }
+}
+
+#[test]
+fn syntax_errors_say_where_they_are() {
+    let json = "{\"format_version\": 1,\n  \"level_solids\": [\n    {\"x\": 0 \"y\": 0}\n  ]\n}";
+
+    match load(json, false) {
+        Err(PhysicsError::LevelParse { line, column, message }) => {
+            // The opening quote of "y", where a comma should have been.
+            assert_eq!((line, column), (3, 13));
+            assert!(message.contains("expected `,`"), "{}", message);
+        }
+        result => panic!("expected a parse error, got {:?}", result.err())
+    }
+    assert!(matches!(load("{\"level_solids\": [", false), Err(PhysicsError::LevelParse { line: 1, column: 18, .. })));
+}
+
+#[test]
+fn invalid_uuids_keep_the_uuid_error() {
+    let json = r#"{"level_solids": [{"x": 0, "y": 0, "width": 8, "height": 8, "uuid": "not a uuid"}]}"#;
+
+    match load(json, false) {
+        Err(PhysicsError::LevelEntry { index: 0, error, .. }) => match *error {
+            PhysicsError::InvalidUuid { value, source } => {
+                assert_eq!(value, "not a uuid");
+                assert_eq!(source, uuid::Uuid::parse_str("not a uuid").unwrap_err());
+            }
+            error => panic!("expected an invalid uuid, got {:?}", error)
+        },
+        result => panic!("expected an error for the solid, got {:?}", result.err())
+    }
+}
+
+#[test]
+fn broken_levels_leave_the_engine_alone() {
+    let mut engine = PhysicsEngine::new();
+    engine.spawn_solid(Collider::new(0, 0, 16, 16), None);
+
+    let result = load_level_from_str(&mut engine, r#"{"level_solids": {}}"#, LoadOptions { clear_solids: true, ..Default::default() });
+
+    assert!(matches!(result, Err(PhysicsError::LevelSchema(_))));
+    assert_eq!(engine.solid_storage.len(), 1);
+}
+
+#[test]
+fn broken_entries_after_good_ones_leave_the_engine_alone() {
+    let mut engine = PhysicsEngine::new();
+    let existing = engine.spawn_solid(Collider::new(0, 0, 16, 16), None);
+    let options = || LoadOptions { clear_solids: true, clear_actors: true, ..Default::default() };
+
+    let bad_uuid = r#"{"level_solids": [
+        {"x": 32, "y": 0, "width": 16, "height": 16, "uuid": null},
+        {"x": 48, "y": 0, "width": 16, "height": 16, "uuid": "not a uuid"}
+    ]}"#;
+    assert!(matches!(load_level_from_str(&mut engine, bad_uuid, options()), Err(PhysicsError::LevelEntry { index: 1, .. })));
+
+    let repeated_uuid = r##"{"level_solids": [
+        {"x": 32, "y": 0, "width": 16, "height": 16, "uuid": "5b1b5e0e-8a5c-4a4e-9c39-2f6d4c1e0a01"}
+    ], "level_tile_grids": [
+        {"x": 0, "y": 32, "tile_size": 8, "rows": ["#"], "uuid": "5b1b5e0e-8a5c-4a4e-9c39-2f6d4c1e0a01"}
+    ]}"##;
+    match load_level_from_str(&mut engine, repeated_uuid, options()) {
+        Err(PhysicsError::LevelEntry { section, index: 0, error }) => {
+            assert_eq!(section, "level_tile_grids");
+            assert!(matches!(*error, PhysicsError::DuplicateSolid(_)));
+        }
+        result => panic!("expected a duplicate tile grid, got {:?}", result)
+    }
+
+    let bad_actor = r#"{"level_solids": [{"x": 32, "y": 0, "width": 16, "height": 16, "uuid": null}], "level_actors": [
+        {"x": 0, "y": 16, "width": 8, "height": 8, "uuid": "5b1b5e0e-8a5c-4a4e-9c39-2f6d4c1e0a02"},
+        {"x": 8, "y": 16, "width": 8, "height": 8, "uuid": "5b1b5e0e-8a5c-4a4e-9c39-2f6d4c1e0a02"}
+    ]}"#;
+    assert!(matches!(load_level_from_str(&mut engine, bad_actor, options()), Err(PhysicsError::LevelEntry { index: 1, .. })));
+
+    assert_eq!(engine.solid_storage.len(), 1);
+    assert_eq!(engine.solid_storage.get_solid(existing).unwrap().collider, Collider::new(0, 0, 16, 16));
+    assert!(engine.actor_storage.is_empty());
+}
@@ -0,0 +1,172 @@
+use minimal_physics_engine::expanded_engine::json_loading::*;
+use minimal_physics_engine::prelude::*;
+
+fn level() -> PhysicsEngine {
+    let mut engine = PhysicsEngine::new();
+
+    engine.spawn_solid(Collider::new(0, 0, 320, 16), None);
+    engine.spawn_solid(Collider::new(64, 32, 32, 8), Some(vec!["moving_platform".to_string(), "red".to_string()]));
+    engine.spawn_solid_with_layers(Collider::new(128, 16, 16, 64), None, CollisionLayers::new(2, 4));
+    engine.spawn_one_way_solid(Collider::new(160, 48, 48, 4), vec2i32(0, 1), Some(vec!["ledge".to_string()]));
+    engine.spawn_slope(Collider::new(208, 16, 32, 16), Slope::UpLeft, None);
+    let hidden = engine.spawn_solid(Collider::new(256, 16, 16, 16), None);
+    engine.solid_storage.get_solid(hidden).unwrap().collider.enabled = false;
+
+    let mut grid = TileGrid::new(8, 3, 2);
+    grid.set(0, 0, Tile::Solid).unwrap();
+    grid.set(1, 0, Tile::Slope(Slope::UpRight)).unwrap();
+    grid.set(2, 1, Tile::OneWay).unwrap();
+    engine.spawn_tile_grid(-24, 0, grid, Some(vec!["tiles".to_string()]));
+
+    engine.spawn_trigger(Collider::new(300, 16, 16, 32), Some(vec!["checkpoint".to_string()]));
+    engine.spawn_trigger_with_layers(Collider::new(0, -64, 320, 16), None, CollisionLayers::new(8, 1));
+
+    engine.spawn_actor(Collider::new(16, 16, 8, 16));
+    let ghost = engine.spawn_actor_with_layers(Collider::new(40, 16, 8, 8), CollisionLayers::new(4, 2));
+    engine.actor_storage.get_actor(ghost).unwrap().collider.enabled = false;
+
+    engine
+}
+
+fn save(engine: &PhysicsEngine) -> String {
+    let mut json = vec![];
+    save_level_to_writer(engine, &mut json).unwrap();
+    String::from_utf8(json).unwrap()
+}
+
+fn load(json: &str) -> PhysicsEngine {
+    let mut engine = PhysicsEngine::new();
+    load_level_from_str(&mut engine, json, LoadOptions::default()).unwrap();
+    engine
+}
+
+#[test]
+fn solids_keep_uuids_tags_and_shapes() {
+    let mut original = level();
+    let mut loaded = load(&save(&original));
+
+    assert_eq!(loaded.solid_storage.len(), original.solid_storage.len());
+    for solid in original.solid_storage.iter() {
+        let loaded_solid = loaded.solid_storage.get_solid(solid.uuid).unwrap();
+        assert_eq!(loaded_solid, solid);
+    }
+    assert_eq!(loaded.solid_storage.get_solids_with_tag("red"), original.solid_storage.get_solids_with_tag("red"));
+    assert_eq!(loaded.solid_storage.get_solids_with_tag("tiles").len(), 1);
+
+    let grid = original.solid_storage.get_solids_with_tag("tiles")[0];
+    assert_eq!(loaded.get_tile(grid, 1, 0).unwrap(), Some(Tile::Slope(Slope::UpRight)));
+    assert_eq!(original.get_tile(grid, 1, 0).unwrap(), Some(Tile::Slope(Slope::UpRight)));
+}
+
+#[test]
+fn triggers_and_actors_keep_uuids_and_colliders() {
+    let original = level();
+    let mut loaded = load(&save(&original));
+
+    assert_eq!(loaded.trigger_storage.len(), original.trigger_storage.len());
+    for trigger in original.trigger_storage.iter() {
+        assert_eq!(loaded.trigger_storage.get_trigger(trigger.uuid).unwrap(), trigger);
+    }
+
+    assert_eq!(loaded.actor_storage.len(), original.actor_storage.len());
+    for actor in original.actor_storage.iter() {
+        let loaded_actor = loaded.actor_storage.get_actor(actor.uuid).unwrap();
+        assert_eq!(loaded_actor.collider, actor.collider);
+        assert_eq!(loaded_actor.layers, actor.layers);
+    }
+}
+
+#[test]
+fn saving_a_loaded_level_gives_the_same_json() {
+    let json = save(&level());
+
+    assert_eq!(save(&load(&json)), json);
+}
+
+#[test]
+fn saved_levels_have_the_format_version() {
+    let json: serde_json::Value = serde_json::from_str(&save(&level())).unwrap();
+
+    assert_eq!(json["format_version"], LEVEL_FORMAT_VERSION);
+}
+
+#[test]
+fn levels_without_new_fields_still_load() {
+    let mut engine = load(r#"{"level_solids": [{"x": 0, "y": 0, "width": 16, "height": 16, "tags": null, "uuid": null}]}"#);
+
+    let solid = engine.solid_storage.iter().next().unwrap().uuid;
+    assert!(engine.solid_storage.get_solid(solid).unwrap().collider.enabled);
+}
+
+#[test]
+fn save_level_to_writes_a_file_load_level_can_read() {
+    let original = level();
+    let path = std::env::temp_dir().join(format!("level_round_trip_{}.json", std::process::id()));
+    let path = path.to_str().unwrap();
+
+    save_level_to(&original, path).unwrap();
+    let json = std::fs::read_to_string(path).unwrap();
+    std::fs::remove_file(path).unwrap();
+
+    assert_eq!(json, save(&original));
+}
+
+#[test]
+fn loading_twice_without_clearing_reports_duplicates() {
+    let json = save(&level());
+    let mut engine = load(&json);
+
+    match load_level_from_str(&mut engine, &json, LoadOptions::default()) {
+        Err(PhysicsError::LevelEntry { section, index, error }) => {
+            assert_eq!((section.as_str(), index), ("level_solids", 0));
+            assert!(matches!(*error, PhysicsError::DuplicateSolid(_)));
+        }
+        result => panic!("expected a duplicate solid, got {:?}", result)
+    }
+    assert!(load_level_from_str(&mut engine, &json, LoadOptions { clear_solids: true, clear_actors: true, ..Default::default() }).is_ok());
+}
+
+#[test]
+fn merging_keeps_solids_with_uuids_from_the_level() {
+    let json = r#"{"format_version": 1, "level_solids": [
+        {"x": 0, "y": 0, "width": 16, "height": 16, "uuid": "5b1b5e0e-8a5c-4a4e-9c39-2f6d4c1e0a01"},
+        {"x": 16, "y": 0, "width": 16, "height": 16, "uuid": null},
+        {"x": 32, "y": 0, "width": 16, "height": 16, "uuid": null}
+    ]}"#;
+    let mut engine = PhysicsEngine::new();
+    load_level_from_str(&mut engine, json, LoadOptions { merge_solids: true, ..Default::default() }).unwrap();
+
+    let named = uuid::uuid!("5b1b5e0e-8a5c-4a4e-9c39-2f6d4c1e0a01");
+    assert_eq!(engine.solid_storage.len(), 2);
+    assert_eq!(engine.solid_storage.get_solid(named).unwrap().collider, Collider::new(0, 0, 16, 16));
+    assert!(engine.solid_storage.iter().any(|solid| solid.collider == Collider::new(16, 0, 32, 16)));
+}
+
+#[test]
+fn levels_saved_without_uuids_merge_when_loaded() {
+    let mut original = PhysicsEngine::new();
+    for x in [0, 16, 32, 48] {
+        original.spawn_solid(Collider::new(x, 0, 16, 16), None);
+    }
+    let merge = LoadOptions { merge_solids: true, ..Default::default() };
+
+    // Saved UUIDs pin every solid, so nothing merges.
+    let mut pinned = PhysicsEngine::new();
+    load_level_from_str(&mut pinned, &save(&original), merge.clone()).unwrap();
+    assert_eq!(pinned.solid_storage.len(), 4);
+
+    let mut json = vec![];
+    save_level_with(&original, &mut json, SaveOptions { skip_uuids: true }).unwrap();
+    let mut merged = PhysicsEngine::new();
+    load_level_from_str(&mut merged, &String::from_utf8(json).unwrap(), merge).unwrap();
+
+    assert_eq!(merged.solid_storage.len(), 1);
+    assert_eq!(merged.solid_storage.iter().next().unwrap().collider, Collider::new(0, 0, 64, 16));
+}
+
+#[test]
+fn unwritable_paths_are_io_errors() {
+    let result = save_level_to(&level(), "/this/directory/does/not/exist/level.json");
+
+    assert!(matches!(result, Err(PhysicsError::LevelIo { .. })));
+}
@@ -0,0 +1,50 @@
+use minimal_physics_engine::prelude::*;
+use uuid::Uuid;
+
+fn collider_of(engine: &mut PhysicsEngine, uuid: Uuid) -> Collider {
+    engine.solid_storage.get_solid(uuid).unwrap().collider.clone()
+}
+
+/// Every pixel covered by a solid in the area.
+fn covered(engine: &PhysicsEngine, area: &Collider) -> Vec<(i32, i32)> {
+    (area.min_y()..area.max_y())
+        .flat_map(|y| (area.min_x()..area.max_x()).map(move |x| (x, y)))
+        .filter(|(x, y)| !engine.query_point(vec2i32(*x, *y), &QueryFilter::default()).solids.is_empty())
+        .collect()
+}
+
+#[test]
+fn kept_uuids_stay_on_their_own_geometry() {
+    let mut engine = PhysicsEngine::new();
+    let top = engine.spawn_solid(Collider::new(0, 16, 16, 16), None);
+    let left = engine.spawn_solid(Collider::new(0, 0, 16, 16), None);
+    let right = engine.spawn_solid(Collider::new(16, 0, 16, 16), None);
+
+    let removed = engine.merge_static_solids();
+
+    assert_eq!(removed, vec![right]);
+    assert_eq!(collider_of(&mut engine, top), Collider::new(0, 16, 16, 16));
+    assert_eq!(collider_of(&mut engine, left), Collider::new(0, 0, 32, 16));
+}
+
+#[test]
+fn every_merged_solid_contains_the_solid_it_was() {
+    let mut engine = PhysicsEngine::new();
+    let mut before = vec![];
+    for (x, y, width, height) in [(0, 0, 16, 48), (16, 0, 32, 16), (16, 16, 16, 16), (48, 0, 16, 16), (80, 0, 16, 16)] {
+        let collider = Collider::new(x, y, width, height);
+        before.push((engine.spawn_solid(collider.clone(), None), collider));
+    }
+    let area = Collider::new(-4, -4, 104, 56);
+    let pixels = covered(&engine, &area);
+
+    let removed = engine.merge_static_solids();
+
+    assert!(!removed.is_empty());
+    assert_eq!(covered(&engine, &area), pixels);
+    for (uuid, collider) in before.into_iter().filter(|(uuid, _)| !removed.contains(uuid)) {
+        let merged = collider_of(&mut engine, uuid);
+        assert!(merged.min_x() <= collider.min_x() && collider.max_x() <= merged.max_x());
+        assert!(merged.min_y() <= collider.min_y() && collider.max_y() <= merged.max_y());
+    }
+}
@@ -0,0 +1,71 @@
+use glam::vec2;
+use minimal_physics_engine::prelude::*;
+
+#[test]
+fn free_moves_report_the_distance() {
+    let mut engine = PhysicsEngine::new();
+    let actor = engine.spawn_actor(Collider::new(0, 0, 8, 8));
+
+    let result = engine.move_actor(actor, vec2(-7.0, 3.0)).unwrap();
+    assert_eq!(result, MoveResult { x: AxisMoveResult { travelled: -7, hit: None }, y: AxisMoveResult { travelled: 3, hit: None } });
+    assert!(!result.blocked());
+
+    // Fractions are kept for later moves rather than travelled.
+    assert_eq!(engine.move_actor(actor, vec2(0.5, 0.0)).unwrap().x.travelled, 0);
+    assert_eq!(engine.move_actor(actor, vec2(0.5, 0.0)).unwrap().x.travelled, 1);
+}
+
+#[test]
+fn each_axis_reports_what_stopped_it() {
+    let mut engine = PhysicsEngine::new();
+    let left_wall = engine.spawn_solid(Collider::new(-20, 0, 10, 40), Some(vec!["wall".to_string(), "left".to_string()]));
+    let ceiling = engine.spawn_solid(Collider::new(-20, 40, 60, 10), Some(vec!["ceiling".to_string()]));
+    let floor = engine.spawn_solid(Collider::new(-20, -10, 60, 10), None);
+    let actor = engine.spawn_actor(Collider::new(0, 10, 8, 8));
+
+    let result = engine.move_actor(actor, vec2(-30.0, 30.0)).unwrap();
+    assert_eq!(result.x, AxisMoveResult {
+        travelled: -10,
+        hit: Some(CollisionInfo { target: CollisionTarget::Solid(left_wall), normal: vec2i32(1, 0), tags: vec!["wall".to_string(), "left".to_string()] })
+    });
+    assert_eq!(result.y, AxisMoveResult {
+        travelled: 22,
+        hit: Some(CollisionInfo { target: CollisionTarget::Solid(ceiling), normal: vec2i32(0, -1), tags: vec!["ceiling".to_string()] })
+    });
+    assert!(result.blocked());
+
+    // Sliding along the floor only blocks the y axis.
+    let result = engine.move_actor(actor, vec2(5.0, -50.0)).unwrap();
+    assert_eq!(result.x, AxisMoveResult { travelled: 5, hit: None });
+    assert_eq!(result.y.travelled, -32);
+    assert_eq!(result.y.hit.as_ref().and_then(|hit| hit.solid_uuid()), Some(floor));
+    assert_eq!(result.y.hit.map(|hit| hit.normal), Some(vec2i32(0, 1)));
+}
+
+#[test]
+fn starting_against_a_solid_travels_nothing() {
+    let mut engine = PhysicsEngine::new();
+    let wall = engine.spawn_solid(Collider::new(8, 0, 10, 10), None);
+    let actor = engine.spawn_actor(Collider::new(0, 0, 8, 8));
+
+    let result = engine.move_actor(actor, vec2(4.0, 0.0)).unwrap();
+
+    assert_eq!(result.x.travelled, 0);
+    assert_eq!(result.x.hit.and_then(|hit| hit.solid_uuid()), Some(wall));
+}
+
+#[test]
+fn actors_that_block_actors_are_reported_as_actors() {
+    let mut engine = PhysicsEngine::new();
+    engine.spawn_solid(Collider::new(30, 0, 10, 10), None);
+    let blocker = engine.spawn_actor(Collider::new(22, 0, 8, 8));
+    engine.actor_storage.get_actor(blocker).unwrap().blocks_actors = true;
+    let actor = engine.spawn_actor(Collider::new(0, 0, 8, 8));
+
+    let result = engine.move_actor(actor, vec2(20.0, 0.0)).unwrap();
+
+    assert_eq!(result.x.travelled, 14);
+    let hit = result.x.hit.unwrap();
+    assert_eq!((hit.actor_uuid(), hit.solid_uuid(), hit.normal), (Some(blocker), None, vec2i32(-1, 0)));
+    assert!(hit.tags.is_empty());
+}
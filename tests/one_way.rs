@@ -0,0 +1,78 @@
+use glam::vec2;
+use minimal_physics_engine::prelude::*;
+use uuid::Uuid;
+
+/// A ledge at y = 20 to 24 that only blocks from above, and an actor below it.
+fn ledge() -> (PhysicsEngine, Uuid, Uuid) {
+    let mut engine = PhysicsEngine::new();
+    let ledge = engine.spawn_one_way_solid(Collider::new(0, 20, 20, 4), vec2i32(0, 1), None);
+    let actor = engine.spawn_actor(Collider::new(4, 0, 8, 8));
+
+    (engine, ledge, actor)
+}
+
+#[test]
+fn passes_up_through() {
+    let (mut engine, _, actor) = ledge();
+
+    // Stop halfway through, then walk sideways and carry on up.
+    let result = engine.move_actor(actor, vec2(0.0, 18.0)).unwrap();
+    assert_eq!(result.y, AxisMoveResult { travelled: 18, hit: None });
+    let result = engine.move_actor(actor, vec2(4.0, 0.0)).unwrap();
+    assert_eq!(result.x, AxisMoveResult { travelled: 4, hit: None });
+    let result = engine.move_actor(actor, vec2(0.0, 22.0)).unwrap();
+    assert_eq!(result.y, AxisMoveResult { travelled: 22, hit: None });
+
+    let collider = &engine.actor_storage.get_actor(actor).unwrap().collider;
+    assert_eq!((collider.x, collider.y), (8, 40));
+}
+
+#[test]
+fn lands_on_top() {
+    let (mut engine, ledge, actor) = ledge();
+    engine.actor_storage.get_actor(actor).unwrap().collider.y = 40;
+
+    let result = engine.move_actor(actor, vec2(0.0, -40.0)).unwrap();
+
+    assert_eq!(result.y, AxisMoveResult {
+        travelled: -16,
+        hit: Some(CollisionInfo { target: CollisionTarget::Solid(ledge), normal: vec2i32(0, 1), tags: vec![] })
+    });
+    assert_eq!(engine.actor_storage.get_actor(actor).unwrap().collider.y, 24);
+    assert_eq!(engine.get_overlapping_solids(actor, vec2i32(0, -1)).unwrap(), vec![ledge]);
+}
+
+#[test]
+fn drops_through_and_falls() {
+    let (mut engine, ledge, actor) = ledge();
+    engine.actor_storage.get_actor(actor).unwrap().collider.y = 24;
+    engine.end_update();
+
+    assert_eq!(engine.drop_through(actor, 2).unwrap(), vec![ledge]);
+    let result = engine.move_actor(actor, vec2(0.0, -6.0)).unwrap();
+    assert_eq!(result.y, AxisMoveResult { travelled: -6, hit: None });
+    assert!(engine.actor_storage.get_actor(actor).unwrap().is_dropping_through(ledge));
+
+    // Once the frames run out the actor is inside the ledge rather than above it, so it keeps falling.
+    engine.end_update();
+    engine.end_update();
+    assert!(!engine.actor_storage.get_actor(actor).unwrap().is_dropping_through(ledge));
+    let result = engine.move_actor(actor, vec2(0.0, -18.0)).unwrap();
+    assert_eq!(result.y, AxisMoveResult { travelled: -18, hit: None });
+    assert_eq!(engine.actor_storage.get_actor(actor).unwrap().collider.y, 0);
+
+    // Coming back down from above, it lands again.
+    engine.move_actor(actor, vec2(0.0, 40.0)).unwrap();
+    assert!(engine.move_actor(actor, vec2(0.0, -40.0)).unwrap().y.blocked());
+    assert_eq!(engine.actor_storage.get_actor(actor).unwrap().collider.y, 24);
+}
+
+#[test]
+fn dropping_through_needs_a_one_way_solid_underneath() {
+    let mut engine = PhysicsEngine::new();
+    engine.spawn_solid(Collider::new(0, 0, 20, 20), None);
+    let actor = engine.spawn_actor(Collider::new(4, 20, 8, 8));
+
+    assert_eq!(engine.drop_through(actor, 10).unwrap(), vec![]);
+    assert!(engine.move_actor(actor, vec2(0.0, -5.0)).unwrap().y.blocked());
+}
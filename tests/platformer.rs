@@ -0,0 +1,190 @@
+use minimal_physics_engine::expanded_engine::prelude::*;
+use minimal_physics_engine::prelude::*;
+use glam::vec2;
+use uuid::Uuid;
+
+const DT: f32 = 1.0 / 60.0;
+
+/// An engine with a wide floor at y = 0 and a controlled actor standing on it at x = 0.
+fn level() -> (PhysicsEngine, PlatformerController) {
+    let mut engine = PhysicsEngine::new();
+    engine.set_gravity(vec2(0.0, -600.0));
+    engine.spawn_solid(Collider::new(-1000, -10, 2000, 10), None);
+    let actor = engine.spawn_actor(Collider::new(0, 0, 8, 8));
+
+    (engine, PlatformerController::new(actor, PlatformerConfig::default()))
+}
+
+fn tick(engine: &mut PhysicsEngine, controller: &mut PlatformerController, horizontal: f32, jump: bool) {
+    controller.update(engine, PlatformerInput { horizontal, jump }, DT).unwrap();
+    engine.end_update();
+}
+
+fn position(engine: &mut PhysicsEngine, actor: Uuid) -> (i32, i32) {
+    let collider = &engine.actor_storage.get_actor(actor).unwrap().collider;
+    (collider.x, collider.y)
+}
+
+/// Holds jump for the number of ticks and returns the highest point reached.
+fn jump_height(held: u32) -> i32 {
+    let (mut engine, mut controller) = level();
+    let mut highest = 0;
+    for frame in 0..120 {
+        tick(&mut engine, &mut controller, 0.0, frame < held);
+        highest = highest.max(position(&mut engine, controller.actor_uuid).1);
+    }
+
+    highest
+}
+
+#[test]
+fn config_loads_with_defaults_for_missing_fields() {
+    let config: PlatformerConfig = serde_json::from_str(r#"{"jump_speed": 300, "ground_snap": 2}"#).unwrap();
+
+    assert_eq!(config.jump_speed, 300.0);
+    assert_eq!(config.ground_snap, 2);
+    assert_eq!(config.run_speed, PlatformerConfig::default().run_speed);
+}
+
+#[test]
+fn running_accelerates_to_top_speed() {
+    let (mut engine, mut controller) = level();
+
+    tick(&mut engine, &mut controller, 1.0, false);
+    assert!(controller.velocity.x > 0.0 && controller.velocity.x < controller.config.run_speed);
+
+    for _ in 0..30 {
+        tick(&mut engine, &mut controller, 1.0, false);
+    }
+    assert_eq!(controller.velocity.x, controller.config.run_speed);
+    assert!(controller.is_grounded());
+}
+
+#[test]
+fn releasing_jump_early_makes_a_lower_jump() {
+    let short = jump_height(3);
+    let long = jump_height(60);
+
+    assert!(short > 0);
+    assert!(short < long);
+}
+
+/// Walks off a ledge, presses jump two ticks later and returns whether it jumped.
+fn jumps_after_leaving_a_ledge(coyote_time: f32) -> bool {
+    let (mut engine, mut controller) = level();
+    controller.config.coyote_time = coyote_time;
+    engine.spawn_solid(Collider::new(-100, 100, 108, 10), None);
+    engine.actor_storage.get_actor(controller.actor_uuid).unwrap().collider.y = 110;
+    tick(&mut engine, &mut controller, 0.0, false);
+
+    while engine.check_overlapping_solid(controller.actor_uuid, vec2i32(0, -1)).unwrap() {
+        tick(&mut engine, &mut controller, 1.0, false);
+    }
+    tick(&mut engine, &mut controller, 1.0, false);
+    tick(&mut engine, &mut controller, 1.0, true);
+
+    controller.velocity.y > 0.0
+}
+
+#[test]
+fn jumps_just_after_leaving_a_ledge() {
+    assert!(jumps_after_leaving_a_ledge(0.1));
+    assert!(!jumps_after_leaving_a_ledge(0.0));
+}
+
+/// Presses jump just above the floor, holds it and returns whether it jumped.
+fn jumps_after_pressing_in_the_air(jump_buffer: f32) -> bool {
+    let (mut engine, mut controller) = level();
+    controller.config.jump_buffer = jump_buffer;
+    engine.actor_storage.get_actor(controller.actor_uuid).unwrap().collider.y = 2;
+
+    tick(&mut engine, &mut controller, 0.0, true);
+    assert!(controller.velocity.y <= 0.0);
+    (0..10).any(|_| {
+        tick(&mut engine, &mut controller, 0.0, true);
+        controller.velocity.y > 0.0
+    })
+}
+
+#[test]
+fn jump_pressed_before_landing_is_buffered() {
+    assert!(jumps_after_pressing_in_the_air(0.2));
+    assert!(!jumps_after_pressing_in_the_air(0.0));
+}
+
+#[test]
+fn slides_down_and_jumps_off_walls() {
+    let (mut engine, mut controller) = level();
+    engine.spawn_solid(Collider::new(20, 0, 10, 500), None);
+    engine.actor_storage.get_actor(controller.actor_uuid).unwrap().collider = Collider::new(12, 300, 8, 8);
+
+    for _ in 0..60 {
+        tick(&mut engine, &mut controller, 1.0, false);
+    }
+    assert!(controller.is_wall_sliding());
+    assert_eq!(controller.velocity.y, -controller.config.wall_slide_speed);
+
+    tick(&mut engine, &mut controller, 1.0, true);
+    assert!(controller.velocity.x < 0.0);
+    assert!(controller.velocity.y > 0.0);
+}
+
+/// Jumps off a platform moving right at 120 pixels per second and returns the speed it jumped with.
+fn jump_off_moving_platform(inherit_platform_velocity: bool) -> f32 {
+    let (mut engine, mut controller) = level();
+    controller.config.inherit_platform_velocity = inherit_platform_velocity;
+    let platform = engine.spawn_solid(Collider::new(-50, 100, 100, 10), None);
+    let actor = engine.actor_storage.get_actor(controller.actor_uuid).unwrap();
+    actor.collider.y = 110;
+    actor.ride_mode = RideMode::Standing;
+
+    for frame in 0..10 {
+        engine.move_solid(platform, vec2(120.0 * DT, 0.0)).unwrap();
+        tick(&mut engine, &mut controller, 0.0, frame == 9);
+    }
+
+    controller.velocity.x
+}
+
+#[test]
+fn jumps_keep_the_speed_of_the_platform() {
+    assert!(jump_off_moving_platform(true) > 60.0);
+    assert_eq!(jump_off_moving_platform(false), 0.0);
+}
+
+#[test]
+fn stays_on_the_ground_running_down_slopes() {
+    let (mut engine, mut controller) = level();
+    engine.spawn_slope(Collider::new(-100, 0, 100, 100), Slope::UpLeft, None);
+    engine.actor_storage.get_actor(controller.actor_uuid).unwrap().collider = Collider::new(-96, 100, 8, 8);
+    for _ in 0..30 {
+        tick(&mut engine, &mut controller, 0.0, false);
+    }
+
+    for _ in 0..40 {
+        tick(&mut engine, &mut controller, 1.0, false);
+        assert!(controller.is_grounded());
+    }
+}
+
+/// Runs down a staircase of two pixel steps and returns whether it stayed on the ground.
+fn stays_on_stairs(ground_snap: i32) -> bool {
+    let (mut engine, mut controller) = level();
+    controller.config.ground_snap = ground_snap;
+    for step in 0..20 {
+        engine.spawn_solid(Collider::new(step * 10, 40 - step * 2, 10, 2), None);
+    }
+    engine.actor_storage.get_actor(controller.actor_uuid).unwrap().collider.y = 42;
+    tick(&mut engine, &mut controller, 0.0, false);
+
+    (0..40).all(|_| {
+        tick(&mut engine, &mut controller, 1.0, false);
+        controller.is_grounded()
+    })
+}
+
+#[test]
+fn snaps_down_small_drops() {
+    assert!(stays_on_stairs(4));
+    assert!(!stays_on_stairs(0));
+}
@@ -0,0 +1,74 @@
+use glam::vec2;
+use minimal_physics_engine::prelude::*;
+
+/// A piston at x = -10 right next to an actor at x = 0, with a wall at x = 10 leaving the actor two pixels.
+fn piston() -> (PhysicsEngine, uuid::Uuid, uuid::Uuid, uuid::Uuid) {
+    let mut engine = PhysicsEngine::new();
+    let piston = engine.spawn_solid(Collider::new(-10, 0, 10, 10), None);
+    let wall = engine.spawn_solid(Collider::new(10, 0, 10, 10), None);
+    let actor = engine.spawn_actor(Collider::new(0, 0, 8, 8));
+
+    (engine, piston, wall, actor)
+}
+
+#[test]
+fn pushed_actors_report_the_push() {
+    let (mut engine, piston, _, actor) = piston();
+
+    engine.move_solid(piston, vec2(2.0, 0.0)).unwrap();
+
+    assert_eq!(engine.actor_storage.get_actor(actor).unwrap().collider.x, 2);
+    assert!(!engine.check_squished(actor).unwrap());
+    assert_eq!(engine.drain_events().collect::<Vec<_>>(), vec![
+        PhysicsEvent::ActorPushed { actor_uuid: actor, solid_uuid: piston, motion: vec2i32(2, 0) }
+    ]);
+}
+
+#[test]
+fn carried_actors_report_the_push() {
+    let mut engine = PhysicsEngine::new();
+    let platform = engine.spawn_solid(Collider::new(0, -10, 40, 10), None);
+    let actor = engine.spawn_actor(Collider::new(10, 0, 8, 8));
+    engine.actor_storage.get_actor(actor).unwrap().ride_mode = RideMode::Standing;
+
+    engine.move_solid(platform, vec2(3.0, 0.0)).unwrap();
+
+    assert_eq!(engine.actor_storage.get_actor(actor).unwrap().collider.x, 13);
+    assert_eq!(engine.drain_events().collect::<Vec<_>>(), vec![
+        PhysicsEvent::ActorPushed { actor_uuid: actor, solid_uuid: platform, motion: vec2i32(3, 0) }
+    ]);
+}
+
+#[test]
+fn pushing_into_a_wall_squishes() {
+    let (mut engine, piston, wall, actor) = piston();
+
+    engine.move_solid(piston, vec2(4.0, 0.0)).unwrap();
+
+    assert!(engine.check_squished(actor).unwrap());
+    assert_eq!(engine.actor_storage.get_actor(actor).unwrap().collider.x, 2);
+    assert_eq!(engine.drain_events().collect::<Vec<_>>(), vec![
+        PhysicsEvent::ActorPushed { actor_uuid: actor, solid_uuid: piston, motion: vec2i32(4, 0) },
+        PhysicsEvent::ActorSquished {
+            actor_uuid: actor,
+            solid_uuid: piston,
+            collision: CollisionInfo { target: CollisionTarget::Solid(wall), normal: vec2i32(-1, 0), tags: vec![] }
+        }
+    ]);
+}
+
+#[test]
+fn pushing_without_squishing_only_blocks() {
+    let (mut engine, piston, wall, actor) = piston();
+
+    engine.move_solid_with_callback(piston, vec2(4.0, 0.0), CollisionCallback::None).unwrap();
+
+    assert!(!engine.check_squished(actor).unwrap());
+    assert_eq!(engine.drain_events().collect::<Vec<_>>(), vec![
+        PhysicsEvent::ActorPushed { actor_uuid: actor, solid_uuid: piston, motion: vec2i32(4, 0) },
+        PhysicsEvent::ActorBlocked {
+            actor_uuid: actor,
+            collision: CollisionInfo { target: CollisionTarget::Solid(wall), normal: vec2i32(-1, 0), tags: vec![] }
+        }
+    ]);
+}
@@ -0,0 +1,187 @@
+mod common;
+
+use glam::vec2;
+use minimal_physics_engine::prelude::*;
+use common::Lcg;
+
+#[test]
+fn query_rect_finds_overlapping_solids_and_actors() {
+    let mut engine = PhysicsEngine::new();
+    let block = engine.spawn_solid(Collider::new(0, 0, 10, 10), None);
+    let slope = engine.spawn_slope(Collider::new(20, 0, 10, 10), Slope::UpRight, None);
+    let actor = engine.spawn_actor(Collider::new(40, 0, 8, 8));
+    let filter = QueryFilter::default();
+
+    assert_eq!(engine.query_rect(&Collider::new(5, 5, 40, 2), &filter), QueryResult { solids: vec![block, slope], actors: vec![actor] });
+    // Touching edges don't overlap.
+    assert!(engine.query_rect(&Collider::new(10, 0, 10, 10), &filter).is_empty());
+    // Above the surface of the slope is free.
+    assert!(engine.query_rect(&Collider::new(20, 8, 2, 2), &filter).is_empty());
+    assert_eq!(engine.query_rect(&Collider::new(28, 0, 2, 2), &filter).solids, vec![slope]);
+}
+
+#[test]
+fn query_rect_follows_the_filter() {
+    let mut engine = PhysicsEngine::new();
+    let tagged = engine.spawn_solid(Collider::new(0, 0, 10, 10), Some(vec!["spikes".to_string()]));
+    let other = engine.spawn_solid_with_layers(Collider::new(0, 0, 10, 10), None, CollisionLayers::new(2, u32::MAX));
+    let actor = engine.spawn_actor(Collider::new(0, 0, 10, 10));
+    engine.spawn_solid(Collider::new(0, 0, 10, 10), None);
+    let disabled = engine.spawn_solid(Collider::new(0, 0, 10, 10), None);
+    engine.solid_storage.get_solid(disabled).unwrap().collider.enabled = false;
+    let area = Collider::new(2, 2, 2, 2);
+
+    let tag = QueryFilter { tag: Some("spikes".to_string()), ..Default::default() };
+    assert_eq!(engine.query_rect(&area, &tag), QueryResult { solids: vec![tagged], actors: vec![] });
+
+    let mask = QueryFilter { mask: 2, ..Default::default() };
+    assert_eq!(engine.query_rect(&area, &mask), QueryResult { solids: vec![other], actors: vec![] });
+
+    let ignored = QueryFilter { ignored: vec![actor], exclude_tag: Some("spikes".to_string()), ..Default::default() };
+    let found = engine.query_rect(&area, &ignored);
+    assert_eq!(found.solids.len(), 2);
+    assert!(!found.solids.contains(&tagged) && !found.solids.contains(&disabled));
+    assert!(found.actors.is_empty());
+}
+
+#[test]
+fn query_point_covers_one_pixel() {
+    let mut engine = PhysicsEngine::new();
+    let block = engine.spawn_solid(Collider::new(0, 0, 10, 10), None);
+
+    assert_eq!(engine.query_point(vec2i32(0, 0), &QueryFilter::default()).solids, vec![block]);
+    assert_eq!(engine.query_point(vec2i32(9, 9), &QueryFilter::default()).solids, vec![block]);
+    assert!(engine.query_point(vec2i32(10, 5), &QueryFilter::default()).is_empty());
+    assert!(engine.query_point(vec2i32(-1, 5), &QueryFilter::default()).is_empty());
+}
+
+#[test]
+fn query_circle_checks_the_round_shape() {
+    let mut engine = PhysicsEngine::new();
+    let block = engine.spawn_solid(Collider::new(0, 0, 10, 10), None);
+    let actor = engine.spawn_actor(Collider::new(30, 0, 8, 8));
+    let filter = QueryFilter::default();
+
+    assert_eq!(engine.query_circle(vec2(5.0, 5.0), 1.0, &filter).solids, vec![block]);
+    assert_eq!(engine.query_circle(vec2(13.0, 5.0), 3.5, &filter).solids, vec![block]);
+    assert!(engine.query_circle(vec2(13.0, 5.0), 2.5, &filter).is_empty());
+    // The corner of the bounding box is in range, but the circle curves away from the block's corner.
+    assert!(engine.query_circle(vec2(13.0, 13.0), 4.0, &filter).is_empty());
+    assert_eq!(engine.query_circle(vec2(13.0, 13.0), 4.5, &filter).solids, vec![block]);
+
+    assert_eq!(engine.query_circle(vec2(20.0, 4.0), 10.5, &filter), QueryResult { solids: vec![block], actors: vec![actor] });
+    assert!(engine.query_circle(vec2(5.0, 5.0), f32::NAN, &filter).is_empty());
+}
+
+#[test]
+fn query_circle_handles_huge_radii() {
+    let mut engine = PhysicsEngine::new();
+    let block = engine.spawn_solid(Collider::new(0, 0, 10, 10), None);
+    let actor = engine.spawn_actor(Collider::new(-5000, 8000, 8, 8));
+
+    for radius in [1e10, f32::MAX, f32::INFINITY] {
+        assert_eq!(engine.query_circle(vec2(0.0, 0.0), radius, &QueryFilter::default()), QueryResult { solids: vec![block], actors: vec![actor] });
+    }
+}
+
+#[test]
+fn shape_cast_reports_where_it_stops() {
+    let mut engine = PhysicsEngine::new();
+    let wall = engine.spawn_solid(Collider::new(20, 0, 10, 40), None);
+    let floor = engine.spawn_solid(Collider::new(-40, -10, 60, 10), None);
+    let collider = Collider::new(0, 10, 8, 8);
+    let filter = QueryFilter::default();
+
+    assert_eq!(
+        engine.shape_cast(&collider, vec2i32(20, 0), &filter),
+        Some(ShapeCastHit { solid_uuid: wall, time: 12.0 / 20.0, offset: vec2i32(12, 0), normal: vec2i32(-1, 0) })
+    );
+    assert_eq!(
+        engine.shape_cast(&collider, vec2i32(0, -20), &filter),
+        Some(ShapeCastHit { solid_uuid: floor, time: 10.0 / 20.0, offset: vec2i32(0, -10), normal: vec2i32(0, 1) })
+    );
+    assert_eq!(
+        engine.shape_cast(&collider, vec2i32(-20, -20), &filter),
+        Some(ShapeCastHit { solid_uuid: floor, time: 10.0 / 20.0, offset: vec2i32(-10, -10), normal: vec2i32(0, 1) })
+    );
+
+    assert_eq!(engine.shape_cast(&collider, vec2i32(12, 0), &filter), None);
+    assert_eq!(engine.shape_cast(&collider, vec2i32(-30, 10), &filter), None);
+    assert_eq!(engine.shape_cast(&collider, vec2i32(20, 0), &QueryFilter { ignored: vec![wall], ..Default::default() }), None);
+}
+
+#[test]
+fn shape_cast_passes_up_through_one_way_solids() {
+    let mut engine = PhysicsEngine::new();
+    let ledge = engine.spawn_one_way_solid(Collider::new(0, 20, 20, 4), vec2i32(0, 1), None);
+    let filter = QueryFilter::default();
+
+    assert_eq!(engine.shape_cast(&Collider::new(4, 0, 8, 8), vec2i32(0, 40), &filter), None);
+    let hit = engine.shape_cast(&Collider::new(4, 40, 8, 8), vec2i32(0, -40), &filter).unwrap();
+    assert_eq!((hit.solid_uuid, hit.offset, hit.normal), (ledge, vec2i32(0, -16), vec2i32(0, 1)));
+}
+
+/// The shape cast as it used to be written: check every step of the line against every solid.
+fn stepped_cast(engine: &PhysicsEngine, collider: &Collider, cast: Vec2I32) -> Option<ShapeCastHit> {
+    let steps = cast.x.abs().max(cast.y.abs());
+    let mut free = vec2i32(0, 0);
+    for step in 1..=steps {
+        let next = vec2i32(
+            (cast.x as f32 * step as f32 / steps as f32).round() as i32,
+            (cast.y as f32 * step as f32 / steps as f32).round() as i32
+        );
+        let motion = vec2i32(next.x - free.x, next.y - free.y);
+
+        for solid in engine.solid_storage.iter() {
+            if !solid.is_overlapping(next, collider) || !solid.blocks(&collider.moved(free), motion) { continue };
+
+            let normal = match motion.x != 0 && solid.is_overlapping(vec2i32(next.x, free.y), collider) {
+                true => vec2i32(-motion.x.signum(), 0),
+                false => vec2i32(0, -motion.y.signum())
+            };
+            return Some(ShapeCastHit { solid_uuid: solid.uuid, time: (step - 1) as f32 / steps as f32, offset: free, normal });
+        }
+        free = next;
+    }
+
+    None
+}
+
+#[test]
+fn shape_cast_matches_stepping_along_the_line() {
+    let mut random = Lcg(5);
+    let mut engine = PhysicsEngine::new();
+    for _ in 0..30 {
+        let collider = Collider::new(random.range(-100, 100), random.range(-100, 100), random.range(2, 30), random.range(2, 30));
+        match random.range(0, 3) {
+            0 => engine.spawn_slope(collider, Slope::UpRight, None),
+            1 => engine.spawn_slope(collider, Slope::UpLeft, None),
+            2 => engine.spawn_one_way_solid(collider, vec2i32(0, 1), None),
+            _ => engine.spawn_solid(collider, None)
+        };
+    }
+    let mut grid = TileGrid::new(8, 4, 3);
+    for (column, row, tile) in [(0, 0, Tile::Solid), (1, 0, Tile::Slope(Slope::UpLeft)), (2, 1, Tile::OneWay), (3, 2, Tile::Solid)] {
+        grid.set(column, row, tile).unwrap();
+    }
+    engine.spawn_tile_grid(-20, -20, grid, None);
+
+    let mut hits = 0;
+    for _ in 0..3000 {
+        let collider = Collider::new(random.range(-120, 120), random.range(-120, 120), random.range(1, 12), random.range(1, 12));
+        let cast = vec2i32(random.range(-150, 150), random.range(-150, 150));
+        let expected = stepped_cast(&engine, &collider, cast);
+        hits += expected.is_some() as i32;
+        assert_eq!(engine.shape_cast(&collider, cast, &QueryFilter::default()), expected, "{:?} cast by {:?}", collider, cast);
+    }
+    assert!(hits > 300, "only {} casts hit anything", hits);
+}
+
+#[test]
+fn shape_cast_handles_long_casts() {
+    let mut engine = PhysicsEngine::new();
+    let far = engine.spawn_solid(Collider::new(1_000_000, 0, 10, 1_000_000), None);
+
+    let hit = engine.shape_cast(&Collider::new(0, 0, 8, 8), vec2i32(200_000_000, 100_000_000), &QueryFilter::default()).unwrap();
+    assert_eq!((hit.solid_uuid, hit.offset, hit.normal), (far, vec2i32(999_992, 499_996), vec2i32(-1, 0)));
+}
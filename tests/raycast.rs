@@ -0,0 +1,79 @@
+use glam::vec2;
+use minimal_physics_engine::prelude::*;
+
+#[test]
+fn reports_distance_point_and_normal() {
+    let mut engine = PhysicsEngine::new();
+    let wall = engine.spawn_solid(Collider::new(10, 0, 10, 10), None);
+
+    let hit = engine.raycast(vec2(0.0, 5.0), vec2(1.0, 0.0), 100.0, &QueryFilter::default()).unwrap();
+    assert_eq!(hit, RayHit { solid_uuid: wall, point: vec2(10.0, 5.0), distance: 10.0, normal: vec2(-1.0, 0.0) });
+
+    let hit = engine.raycast(vec2(15.0, 30.0), vec2(0.0, -2.0), 100.0, &QueryFilter::default()).unwrap();
+    assert_eq!((hit.distance, hit.normal), (20.0, vec2(0.0, 1.0)));
+
+    let hit = engine.raycast(vec2(0.0, 25.0), vec2(1.0, -1.0), 100.0, &QueryFilter::default()).unwrap();
+    assert!((hit.distance - 15.0 * 2.0f32.sqrt()).abs() < 1e-4);
+    assert_eq!(hit.normal, vec2(0.0, 1.0));
+}
+
+#[test]
+fn stops_at_the_max_distance() {
+    let mut engine = PhysicsEngine::new();
+    engine.spawn_solid(Collider::new(10, 0, 10, 10), None);
+
+    assert!(engine.raycast(vec2(0.0, 5.0), vec2(1.0, 0.0), 9.5, &QueryFilter::default()).is_none());
+    assert!(engine.raycast(vec2(0.0, 5.0), vec2(1.0, 0.0), 10.0, &QueryFilter::default()).is_some());
+    assert!(engine.raycast(vec2(0.0, 5.0), vec2(-1.0, 0.0), 100.0, &QueryFilter::default()).is_none());
+}
+
+#[test]
+fn starting_inside_hits_straight_away() {
+    let mut engine = PhysicsEngine::new();
+    engine.spawn_solid(Collider::new(0, 0, 10, 10), None);
+
+    let hit = engine.raycast(vec2(5.0, 5.0), vec2(1.0, 0.0), 100.0, &QueryFilter::default()).unwrap();
+    assert_eq!((hit.distance, hit.normal), (0.0, vec2(0.0, 0.0)));
+}
+
+#[test]
+fn hits_are_sorted_and_filtered() {
+    let mut engine = PhysicsEngine::new();
+    let near = engine.spawn_solid(Collider::new(10, 0, 4, 10), Some(vec!["glass".to_string()]));
+    let middle = engine.spawn_solid_with_layers(Collider::new(20, 0, 4, 10), None, CollisionLayers::new(2, u32::MAX));
+    let far = engine.spawn_solid(Collider::new(30, 0, 4, 10), None);
+    let ray = |filter: &QueryFilter| engine.raycast_all(vec2(0.0, 5.0), vec2(1.0, 0.0), 100.0, filter).iter().map(|hit| hit.solid_uuid).collect::<Vec<_>>();
+
+    assert_eq!(ray(&QueryFilter::default()), vec![near, middle, far]);
+    assert_eq!(ray(&QueryFilter { exclude_tag: Some("glass".to_string()), ..Default::default() }), vec![middle, far]);
+    assert_eq!(ray(&QueryFilter { tag: Some("glass".to_string()), ..Default::default() }), vec![near]);
+    assert_eq!(ray(&QueryFilter { mask: 1, ..Default::default() }), vec![near, far]);
+    assert_eq!(ray(&QueryFilter { ignored: vec![near, far], ..Default::default() }), vec![middle]);
+}
+
+#[test]
+fn one_way_solids_are_only_hit_through_their_face() {
+    let mut engine = PhysicsEngine::new();
+    let ledge = engine.spawn_one_way_solid(Collider::new(0, 0, 20, 4), vec2i32(0, 1), None);
+
+    let hit = engine.raycast(vec2(10.0, 20.0), vec2(0.0, -1.0), 100.0, &QueryFilter::default()).unwrap();
+    assert_eq!((hit.solid_uuid, hit.distance, hit.normal), (ledge, 16.0, vec2(0.0, 1.0)));
+
+    assert!(engine.raycast(vec2(10.0, -20.0), vec2(0.0, 1.0), 100.0, &QueryFilter::default()).is_none());
+    assert!(engine.raycast(vec2(-10.0, 2.0), vec2(1.0, 0.0), 100.0, &QueryFilter::default()).is_none());
+    assert!(engine.raycast(vec2(10.0, 2.0), vec2(0.0, -1.0), 100.0, &QueryFilter::default()).is_none());
+}
+
+#[test]
+fn handles_huge_distances() {
+    let mut engine = PhysicsEngine::new();
+    let wall = engine.spawn_solid(Collider::new(100, 100, 10, 10), None);
+
+    for max_distance in [f32::MAX, f32::INFINITY, 1e30, 200_000.0] {
+        let hit = engine.raycast(vec2(0.0, 0.0), vec2(1.0, 1.0), max_distance, &QueryFilter::default()).unwrap();
+        assert_eq!(hit.solid_uuid, wall);
+        assert!((hit.distance - 100.0 * 2.0f32.sqrt()).abs() < 1e-3);
+
+        assert!(engine.raycast(vec2(0.0, 0.0), vec2(-1.0, -1.0), max_distance, &QueryFilter::default()).is_none());
+    }
+}
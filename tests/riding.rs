@@ -0,0 +1,22 @@
+use minimal_physics_engine::prelude::*;
+
+#[test]
+fn standing_still_does_not_start_and_stop_riding() {
+    let mut engine = PhysicsEngine::new();
+    let ground = engine.spawn_solid(Collider::new(0, 0, 100, 16), None);
+    let platform = engine.spawn_solid(Collider::new(500, 0, 32, 8), None);
+    let actor = engine.spawn_actor(Collider::new(10, 16, 8, 8));
+    engine.actor_storage.get_actor(actor).unwrap().ride_mode = RideMode::Standing;
+
+    let mut events = vec![];
+    for frame in 0..6 {
+        // A platform far away only moves every other frame.
+        if frame % 2 == 0 {
+            engine.move_solid(platform, glam::vec2(1.0, 0.0)).unwrap();
+        }
+        engine.end_update();
+        events.extend(engine.drain_events());
+    }
+
+    assert_eq!(events, vec![PhysicsEvent::ActorStartedRiding { actor_uuid: actor, solid_uuid: ground }]);
+}
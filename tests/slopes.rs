@@ -0,0 +1,130 @@
+use glam::vec2;
+use minimal_physics_engine::prelude::*;
+use uuid::Uuid;
+
+/// Run and rise of a 45° slope, the 22.5° pixel art slope and a steep one rising three pixels per column.
+const SLOPES: [(i32, i32); 3] = [(32, 32), (64, 32), (16, 48)];
+
+/// A hill on flat ground at y = 0: a slope up to a plateau at the slope's rise, then a slope back down.
+fn hill(run: i32, rise: i32) -> (PhysicsEngine, Uuid) {
+    let mut engine = PhysicsEngine::new();
+    engine.spawn_solid(Collider::new(-100, -16, 2 * run + 240, 16), None);
+    engine.spawn_slope(Collider::new(0, 0, run, rise), Slope::UpRight, None);
+    engine.spawn_solid(Collider::new(run, 0, 40, rise), None);
+    engine.spawn_slope(Collider::new(run + 40, 0, run, rise), Slope::UpLeft, None);
+    let actor = engine.spawn_actor(Collider::new(-40, 0, 8, 8));
+
+    (engine, actor)
+}
+
+/// The ground under the actor on the hill, found by shape rather than through the engine.
+fn ground_height(run: i32, rise: i32, actor: &Collider) -> i32 {
+    let up = Slope::UpRight.surface_under(&Collider::new(0, 0, run, rise), actor);
+    let plateau = (actor.max_x() > run && actor.min_x() < run + 40).then_some(rise);
+    let down = Slope::UpLeft.surface_under(&Collider::new(run + 40, 0, run, rise), actor);
+
+    [up, plateau, down].into_iter().flatten().max().unwrap_or(0)
+}
+
+/// Walks the actor across the hill in steps of `speed` pixels without any gravity,
+/// checking it stands right on the surface after every step, and returns the highest it got.
+fn walk_over_hill(run: i32, rise: i32, speed: f32) -> i32 {
+    let (mut engine, actor) = hill(run, rise);
+    if speed < 0.0 {
+        engine.actor_storage.get_actor(actor).unwrap().collider.x = 2 * run + 80;
+    }
+
+    let mut highest = 0;
+    let steps = ((2 * run + 120) as f32 / speed.abs()) as i32;
+    for _ in 0..steps {
+        let result = engine.move_actor(actor, vec2(speed, 0.0)).unwrap();
+        assert!(!result.x.blocked(), "{}x{} slope blocked the walk", run, rise);
+
+        let collider = engine.actor_storage.get_actor(actor).unwrap().collider.clone();
+        assert_eq!(collider.y, ground_height(run, rise, &collider), "{}x{} slope at {:?}", run, rise, collider);
+        assert!(!engine.check_overlapping_solid(actor, vec2i32(0, 0)).unwrap());
+        highest = highest.max(collider.y);
+    }
+
+    let collider = &engine.actor_storage.get_actor(actor).unwrap().collider;
+    match speed > 0.0 {
+        true => assert!(collider.min_x() > 2 * run + 40, "{}x{} slope stopped at {:?}", run, rise, collider),
+        false => assert!(collider.max_x() < 0, "{}x{} slope stopped at {:?}", run, rise, collider)
+    }
+    highest
+}
+
+#[test]
+fn walks_up_and_down_slopes() {
+    for (run, rise) in SLOPES {
+        for speed in [1.0, 3.0, -1.0, -3.0] {
+            assert_eq!(walk_over_hill(run, rise, speed), rise);
+        }
+    }
+}
+
+#[test]
+fn sticks_to_slopes_walking_down() {
+    for (run, rise) in SLOPES {
+        let (mut engine, actor) = hill(run, rise);
+        // Start at the top of the slope going down to the right, as if walking off the plateau.
+        engine.actor_storage.get_actor(actor).unwrap().collider = Collider::new(run + 36, rise, 8, 8);
+
+        for _ in 0..run / 2 {
+            engine.move_actor(actor, vec2(2.0, 0.0)).unwrap();
+            assert!(engine.check_overlapping_solid(actor, vec2i32(0, -1)).unwrap(), "{}x{} slope left the actor in the air", run, rise);
+        }
+    }
+}
+
+#[test]
+fn only_actors_falling_down_y_stick_to_slopes() {
+    let (mut engine, actor) = hill(32, 32);
+    let top = Collider::new(72, 32, 8, 8);
+    engine.actor_storage.get_actor(actor).unwrap().collider = top.clone();
+    engine.actor_storage.get_actor(actor).unwrap().down = vec2i32(0, 1);
+
+    engine.move_actor(actor, vec2(4.0, 0.0)).unwrap();
+    assert_eq!(engine.actor_storage.get_actor(actor).unwrap().collider.y, 32);
+
+    let mut alone = Actor::new(top, Uuid::new_v4());
+    alone.down = vec2i32(0, 1);
+    alone.move_exact(vec2i32(4, 0), &mut CollisionCallback::None, &engine.solid_storage);
+    assert_eq!((alone.collider.x, alone.collider.y), (76, 32));
+}
+
+#[test]
+fn walking_off_a_flat_ledge_does_not_stick() {
+    let mut engine = PhysicsEngine::new();
+    engine.spawn_solid(Collider::new(0, 0, 20, 20), None);
+    let actor = engine.spawn_actor(Collider::new(10, 20, 8, 8));
+
+    engine.move_actor(actor, vec2(16.0, 0.0)).unwrap();
+
+    assert_eq!(engine.actor_storage.get_actor(actor).unwrap().collider.y, 20);
+}
+
+#[test]
+fn rides_moving_slopes() {
+    for (run, rise) in SLOPES {
+        let mut engine = PhysicsEngine::new();
+        let slope = engine.spawn_slope(Collider::new(0, 0, run, rise), Slope::UpRight, None);
+        let actor = engine.spawn_actor(Collider::new(run / 2, 0, 8, 8));
+        engine.actor_storage.get_actor(actor).unwrap().ride_mode = RideMode::Standing;
+        let surface = Slope::UpRight.surface_under(&Collider::new(0, 0, run, rise), &Collider::new(run / 2, 0, 8, 8)).unwrap();
+        engine.actor_storage.get_actor(actor).unwrap().collider.y = surface;
+        engine.end_update();
+
+        for motion in [vec2(2.0, 0.0), vec2(-3.0, 0.0), vec2(1.0, 2.0), vec2(0.0, -2.0), vec2(-2.0, -1.0)] {
+            for _ in 0..5 {
+                engine.move_solid(slope, motion).unwrap();
+                engine.end_update();
+
+                let slope_collider = engine.solid_storage.get_solid(slope).unwrap().collider.clone();
+                let collider = &engine.actor_storage.get_actor(actor).unwrap().collider;
+                assert_eq!(collider.x - slope_collider.x, run / 2, "{}x{} slope moving {:?}", run, rise, motion);
+                assert_eq!(collider.y - slope_collider.y, surface, "{}x{} slope moving {:?}", run, rise, motion);
+            }
+        }
+    }
+}
@@ -0,0 +1,124 @@
+mod common;
+
+use glam::{vec2, Vec2};
+use minimal_physics_engine::prelude::*;
+use common::Lcg;
+
+/// The per-pixel loop the sweeps replaced: step one pixel at a time until the next one would overlap.
+fn stepped(collider: &Collider, axis: Axis, distance: i32, other: &Collider) -> Option<i32> {
+    let step = axis.vec(distance.signum());
+    (0..distance.abs()).find(|taken| collider.is_overlapping(vec2i32(step.x * (taken + 1), step.y * (taken + 1)), other))
+}
+
+#[test]
+fn sweeps_match_stepping_one_pixel_at_a_time() {
+    for (width, height) in [(1, 1), (3, 1), (1, 5), (5, 3)] {
+        let collider = Collider::new(0, 0, width, height);
+        for x in -8..=8 {
+            for y in -8..=8 {
+                for (other_width, other_height) in [(1, 1), (2, 5), (5, 2)] {
+                    let other = Collider::new(x, y, other_width, other_height);
+                    for distance in -10..=10 {
+                        for axis in [Axis::X, Axis::Y] {
+                            assert_eq!(
+                                collider.sweep(axis, distance, &other), stepped(&collider, axis, distance, &other),
+                                "{:?} moving {} along {:?} towards {:?}", collider, distance, axis, other
+                            );
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn sweeps_ignore_disabled_colliders() {
+    let collider = Collider::new(0, 0, 4, 4);
+    let mut other = Collider::new(6, 0, 4, 4);
+    assert_eq!(collider.sweep_x(10, &other), Some(2));
+
+    other.enabled = false;
+    assert_eq!(collider.sweep_x(10, &other), None);
+    assert_eq!(other.sweep_x(-10, &collider), None);
+}
+
+/// An actor moved the way the engine did before sweeps: whole pixels from the remainder, one pixel at a time.
+struct SteppedActor {
+    collider: Collider,
+    remainder: Vec2,
+    hits: Vec<Vec2I32>
+}
+
+impl SteppedActor {
+    fn move_by(&mut self, distance: Vec2, solids: &[Collider]) {
+        self.remainder += distance;
+        let move_amount = vec2i32(self.remainder.x as i32, self.remainder.y as i32);
+        self.remainder.x -= move_amount.x as f32;
+        self.remainder.y -= move_amount.y as f32;
+
+        self.move_along(Axis::X, move_amount.x, solids);
+        self.move_along(Axis::Y, move_amount.y, solids);
+    }
+
+    fn move_along(&mut self, axis: Axis, distance: i32, solids: &[Collider]) {
+        let step = axis.vec(distance.signum());
+        for _ in 0..distance.abs() {
+            if solids.iter().any(|solid| self.collider.is_overlapping(step, solid)) {
+                match axis {
+                    Axis::X => self.remainder.x = 0.0,
+                    Axis::Y => self.remainder.y = 0.0
+                }
+                self.hits.push(vec2i32(-step.x, -step.y));
+                return;
+            }
+            self.collider.translate(axis, distance.signum());
+        }
+    }
+}
+
+#[test]
+fn moves_match_stepping_one_pixel_at_a_time() {
+    for broadphase in [Broadphase::BruteForce, Broadphase::SpatialHash { cell_size: 16 }] {
+        let mut random = Lcg(7);
+        let mut engine = PhysicsEngine::with_broadphase(broadphase);
+
+        // Scattered blocks around an actor that starts in a clear spot.
+        let start = Collider::new(0, 0, 6, 10);
+        let mut solids = vec![];
+        while solids.len() < 40 {
+            let solid = Collider::new(random.range(-120, 120), random.range(-120, 120), random.range(1, 24), random.range(1, 24));
+            if !start.is_overlapping(vec2i32(0, 0), &solid) {
+                engine.spawn_solid(solid.clone(), None);
+                solids.push(solid);
+            }
+        }
+        // Walls so it can't wander off.
+        for wall in [Collider::new(-140, -140, 280, 10), Collider::new(-140, 130, 280, 10), Collider::new(-140, -140, 10, 280), Collider::new(130, -140, 10, 280)] {
+            engine.spawn_solid(wall.clone(), None);
+            solids.push(wall);
+        }
+
+        let actor = engine.spawn_actor(start.clone());
+        let mut reference = SteppedActor { collider: start, remainder: Vec2::ZERO, hits: vec![] };
+        let mut blocked = 0;
+
+        for _ in 0..2000 {
+            let distance = vec2(random.range(-1200, 1200) as f32 / 100.0, random.range(-1200, 1200) as f32 / 100.0);
+            reference.move_by(distance, &solids);
+
+            let mut hits = vec![];
+            let mut record = |_: &mut Actor, info: &CollisionInfo| hits.push(info.normal);
+            let result = engine.move_actor_with_callback(actor, distance, CollisionCallback::Custom(&mut record)).unwrap();
+            let moved = engine.actor_storage.get_actor(actor).unwrap();
+
+            assert_eq!(moved.collider, reference.collider, "moving {:?} with {:?}", distance, broadphase);
+            assert_eq!(moved.remainder, reference.remainder, "moving {:?} with {:?}", distance, broadphase);
+            assert_eq!(hits.len(), result.x.blocked() as usize + result.y.blocked() as usize);
+            blocked += hits.len();
+            assert_eq!(reference.hits.drain(..).collect::<Vec<_>>(), hits, "moving {:?} with {:?}", distance, broadphase);
+        }
+        // Make sure the moves actually ran into things.
+        assert!(blocked > 200, "only {} moves were blocked", blocked);
+    }
+}
@@ -0,0 +1,80 @@
+use glam::vec2;
+use minimal_physics_engine::prelude::*;
+use uuid::Uuid;
+
+/// A 6 by 3 grid of 16 pixel tiles at the origin: a solid floor, a ledge over column 2 and a wall in column 4.
+fn room() -> (PhysicsEngine, Uuid) {
+    let mut grid = TileGrid::new(16, 6, 3);
+    for column in 0..6 {
+        grid.set(column, 0, Tile::Solid).unwrap();
+    }
+    grid.set(2, 1, Tile::OneWay).unwrap();
+    grid.set(4, 1, Tile::Solid).unwrap();
+
+    let mut engine = PhysicsEngine::new();
+    let grid = engine.spawn_tile_grid(0, 0, grid, Some(vec!["tiles".to_string()]));
+    (engine, grid)
+}
+
+#[test]
+fn tiles_collide_like_solids() {
+    let (mut engine, grid) = room();
+    let actor = engine.spawn_actor(Collider::new(2, 40, 8, 8));
+
+    let result = engine.move_actor(actor, vec2(0.0, -40.0)).unwrap();
+    assert_eq!(result.y, AxisMoveResult {
+        travelled: -24,
+        hit: Some(CollisionInfo { target: CollisionTarget::Solid(grid), normal: vec2i32(0, 1), tags: vec!["tiles".to_string()] })
+    });
+
+    // Walking through the side of the ledge, then into the wall.
+    let result = engine.move_actor(actor, vec2(80.0, 0.0)).unwrap();
+    assert_eq!(result.x, AxisMoveResult {
+        travelled: 54,
+        hit: Some(CollisionInfo { target: CollisionTarget::Solid(grid), normal: vec2i32(-1, 0), tags: vec!["tiles".to_string()] })
+    });
+
+    // The ledge only blocks from above.
+    let ledge = engine.spawn_actor(Collider::new(36, 50, 8, 8));
+    let result = engine.move_actor(ledge, vec2(0.0, -40.0)).unwrap();
+    assert_eq!(result.y.travelled, -18);
+    assert_eq!(engine.actor_storage.get_actor(ledge).unwrap().collider.y, 32);
+}
+
+#[test]
+fn set_tile_changes_collision_straight_away() {
+    let (mut engine, grid) = room();
+    let actor = engine.spawn_actor(Collider::new(2, 16, 8, 8));
+
+    engine.clear_tile(grid, 4, 1).unwrap();
+    engine.set_tile(grid, 5, 1, Tile::Solid).unwrap();
+    assert_eq!(engine.get_tile(grid, 4, 1).unwrap(), Some(Tile::Empty));
+    assert_eq!(engine.get_tile(grid, 5, 1).unwrap(), Some(Tile::Solid));
+
+    let result = engine.move_actor(actor, vec2(90.0, 0.0)).unwrap();
+    assert_eq!(result.x.travelled, 70);
+    assert!(result.x.blocked());
+
+    // Digging a hole in the floor under the actor lets it fall through.
+    engine.clear_tile(grid, 4, 0).unwrap();
+    assert!(!engine.check_overlapping_solid(actor, vec2i32(0, -1)).unwrap());
+    let result = engine.move_actor(actor, vec2(0.0, -30.0)).unwrap();
+    assert_eq!(result.y, AxisMoveResult { travelled: -30, hit: None });
+
+    // Filling it back in blocks the way back up.
+    engine.set_tile(grid, 4, 0, Tile::Solid).unwrap();
+    assert!(engine.move_actor(actor, vec2(0.0, 30.0)).unwrap().y.blocked());
+    assert_eq!(engine.actor_storage.get_actor(actor).unwrap().collider.y, -8);
+}
+
+#[test]
+fn set_tile_rejects_bad_tiles() {
+    let (mut engine, grid) = room();
+    let block = engine.spawn_solid(Collider::new(100, 0, 16, 16), None);
+
+    assert_eq!(engine.set_tile(grid, 6, 0, Tile::Solid), Err(PhysicsError::TileOutOfBounds { column: 6, row: 0 }));
+    assert_eq!(engine.set_tile(grid, 0, -1, Tile::Solid), Err(PhysicsError::TileOutOfBounds { column: 0, row: -1 }));
+    assert_eq!(engine.get_tile(grid, 6, 0).unwrap(), None);
+    assert_eq!(engine.set_tile(block, 0, 0, Tile::Solid), Err(PhysicsError::NotATileGrid(block)));
+    assert_eq!(engine.get_tile(block, 0, 0), Err(PhysicsError::NotATileGrid(block)));
+}
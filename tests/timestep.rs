@@ -0,0 +1,51 @@
+use minimal_physics_engine::prelude::*;
+
+/// Runs a falling, sliding actor for two seconds of frames at the framerate and returns where it ends up.
+fn simulate(framerate: f32) -> (Collider, u32) {
+    let mut engine = PhysicsEngine::new();
+    engine.spawn_solid(Collider::new(-1000, -200, 2000, 10), None);
+    let actor = engine.spawn_actor(Collider::new(0, 100, 8, 8));
+
+    let mut velocity = glam::vec2(37.0, 0.0);
+    let mut ticks = 0;
+    for _ in 0..(framerate * 2.0).round() as u32 {
+        ticks += engine.step(1.0 / framerate, |engine, dt| {
+            velocity.y -= 300.0 * dt;
+            let result = engine.move_actor(actor, velocity * dt)?;
+            if result.y.blocked() {
+                velocity.y = 0.0;
+            }
+            Ok(())
+        }).unwrap();
+    }
+
+    (engine.actor_storage.get_actor(actor).unwrap().collider.clone(), ticks)
+}
+
+#[test]
+fn framerate_does_not_change_the_result() {
+    let expected = simulate(60.0);
+
+    assert_eq!(expected.1, 120);
+    assert_eq!(simulate(30.0), expected);
+    assert_eq!(simulate(144.0), expected);
+}
+
+#[test]
+fn leftover_time_carries_over() {
+    let mut timestep = FixedTimestep::new(60.0);
+
+    assert_eq!(timestep.advance(1.0 / 144.0), 0);
+    assert_eq!(timestep.advance(1.0 / 144.0), 0);
+    assert!((timestep.alpha() - 120.0 / 144.0).abs() < 1e-4);
+    assert_eq!(timestep.advance(1.0 / 144.0), 1);
+}
+
+#[test]
+fn long_frames_are_capped() {
+    let mut timestep = FixedTimestep::new(60.0);
+    timestep.max_ticks = 4;
+
+    assert_eq!(timestep.advance(1.0), 4);
+    assert_eq!(timestep.alpha(), 0.0);
+}
@@ -0,0 +1,83 @@
+use glam::vec2;
+use minimal_physics_engine::prelude::*;
+
+fn trigger_events(engine: &mut PhysicsEngine) -> Vec<PhysicsEvent> {
+    engine.drain_events()
+        .filter(|event| matches!(event, PhysicsEvent::TriggerEntered { .. } | PhysicsEvent::TriggerStayed { .. } | PhysicsEvent::TriggerExited { .. }))
+        .collect()
+}
+
+#[test]
+fn reports_actors_entering_staying_and_leaving() {
+    let mut engine = PhysicsEngine::new();
+    let trigger = engine.spawn_trigger(Collider::new(20, 0, 10, 10), Some(vec!["checkpoint".to_string()]));
+    let actor = engine.spawn_actor(Collider::new(0, 0, 8, 8));
+
+    engine.end_update();
+    assert_eq!(trigger_events(&mut engine), vec![]);
+
+    // Triggers never block.
+    let result = engine.move_actor(actor, vec2(16.0, 0.0)).unwrap();
+    assert_eq!(result.x, AxisMoveResult { travelled: 16, hit: None });
+    engine.end_update();
+    assert_eq!(trigger_events(&mut engine), vec![PhysicsEvent::TriggerEntered { actor_uuid: actor, trigger_uuid: trigger }]);
+
+    engine.end_update();
+    assert_eq!(trigger_events(&mut engine), vec![PhysicsEvent::TriggerStayed { actor_uuid: actor, trigger_uuid: trigger }]);
+
+    engine.move_actor(actor, vec2(20.0, 0.0)).unwrap();
+    engine.end_update();
+    assert_eq!(trigger_events(&mut engine), vec![PhysicsEvent::TriggerExited { actor_uuid: actor, trigger_uuid: trigger }]);
+}
+
+#[test]
+fn moving_a_trigger_onto_an_actor_enters_it() {
+    let mut engine = PhysicsEngine::new();
+    let trigger = engine.spawn_trigger(Collider::new(-20, 0, 10, 10), None);
+    let actor = engine.spawn_actor(Collider::new(0, 0, 8, 8));
+    engine.end_update();
+
+    // Fractions of a pixel add up over moves.
+    for _ in 0..21 {
+        engine.move_trigger(trigger, vec2(0.5, 0.0)).unwrap();
+    }
+    assert_eq!(engine.trigger_storage.get_trigger(trigger).unwrap().collider.x, -10);
+    assert_eq!(engine.get_actors_in_trigger(trigger).unwrap(), vec![]);
+
+    engine.move_trigger(trigger, vec2(4.0, 0.0)).unwrap();
+    assert_eq!(engine.get_actors_in_trigger(trigger).unwrap(), vec![actor]);
+    assert_eq!(engine.get_overlapping_triggers(actor).unwrap(), vec![trigger]);
+    // The actor isn't pushed along.
+    assert_eq!(engine.actor_storage.get_actor(actor).unwrap().collider.x, 0);
+
+    engine.end_update();
+    assert_eq!(trigger_events(&mut engine), vec![PhysicsEvent::TriggerEntered { actor_uuid: actor, trigger_uuid: trigger }]);
+}
+
+#[test]
+fn actors_in_trigger_follow_layers() {
+    let mut engine = PhysicsEngine::new();
+    let trigger = engine.spawn_trigger_with_layers(Collider::new(0, 0, 40, 10), None, CollisionLayers::new(1, 2));
+    let player = engine.spawn_actor_with_layers(Collider::new(0, 0, 8, 8), CollisionLayers::new(2, u32::MAX));
+    engine.spawn_actor_with_layers(Collider::new(10, 0, 8, 8), CollisionLayers::new(4, u32::MAX));
+    engine.spawn_actor(Collider::new(60, 0, 8, 8));
+
+    assert_eq!(engine.get_actors_in_trigger(trigger).unwrap(), vec![player]);
+    engine.end_update();
+    assert_eq!(trigger_events(&mut engine), vec![PhysicsEvent::TriggerEntered { actor_uuid: player, trigger_uuid: trigger }]);
+}
+
+#[test]
+fn despawned_triggers_report_their_actors_leaving() {
+    let mut engine = PhysicsEngine::new();
+    let trigger = engine.spawn_trigger(Collider::new(0, 0, 10, 10), None);
+    let actor = engine.spawn_actor(Collider::new(0, 0, 8, 8));
+    engine.end_update();
+    trigger_events(&mut engine);
+
+    engine.despawn_trigger(trigger).unwrap();
+    engine.end_update();
+
+    assert_eq!(trigger_events(&mut engine), vec![PhysicsEvent::TriggerExited { actor_uuid: actor, trigger_uuid: trigger }]);
+    assert!(engine.get_actors_in_trigger(trigger).is_err());
+}